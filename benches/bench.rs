@@ -1,34 +1,16 @@
 //! F14VectorMap 基准测试
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 
-use f14vectormap::{traits::HasherExt, F14VectorMap};
+use f14vectormap::{traits::HasherExt, F14ValueMap, F14VectorMap};
 use std::collections::HashMap;
 
 const SIZE: usize = 1000;
 
 fn bench_f14_insert(c: &mut Criterion) {
     c.bench_function("f14_insert", |b| {
-
-         #[derive(Clone)]
-    struct FixedHasher;
-    
-    impl std::hash::BuildHasher for FixedHasher {
-        type Hasher = FixedHasherImpl;
-        
-        fn build_hasher(&self) -> Self::Hasher {
-            FixedHasherImpl(0)
-        }
-    }
-     struct FixedHasherImpl(u64);
-    impl std::hash::Hasher for FixedHasherImpl {
-        fn write(&mut self, _: &[u8]) {}
-        fn finish(&self) -> u64 { self.0 }
-    }
-     
         b.iter(|| {
-            let mut map: HashMap<usize, usize, FixedHasher> = 
-                HashMap::with_hasher(FixedHasher);
+           let mut map: F14VectorMap<usize, usize> = F14VectorMap::new().unwrap();
             for i in 0..SIZE {
                 map.insert(i, i).unwrap();
             }
@@ -48,23 +30,7 @@ fn bench_std_insert(c: &mut Criterion) {
 }
 
 fn bench_f14_get(c: &mut Criterion) {
-    #[derive(Clone)]
-    struct FixedHasher;
-    
-    impl std::hash::BuildHasher for FixedHasher {
-        type Hasher = FixedHasherImpl;
-        
-        fn build_hasher(&self) -> Self::Hasher {
-            FixedHasherImpl(0)
-        }
-    }
-     struct FixedHasherImpl(u64);
-    impl std::hash::Hasher for FixedHasherImpl {
-        fn write(&mut self, _: &[u8]) {}
-        fn finish(&self) -> u64 { self.0 }
-    }
-     let mut map: HashMap<usize, usize, FixedHasher> = 
-                HashMap::with_hasher(FixedHasher);
+    let mut map: F14VectorMap<usize, usize> = F14VectorMap::new().unwrap();
     for i in 0..SIZE {
         map.insert(i, i).unwrap();
     }
@@ -93,24 +59,39 @@ fn bench_std_get(c: &mut Criterion) {
     });
 }
 
-fn bench_f14_remove(c: &mut Criterion) {
-    #[derive(Clone)]
-    struct FixedHasher;
-    
-    impl std::hash::BuildHasher for FixedHasher {
-        type Hasher = FixedHasherImpl;
-        
-        fn build_hasher(&self) -> Self::Hasher {
-            FixedHasherImpl(0)
-        }
+fn bench_f14_get_miss(c: &mut Criterion) {
+    // 未命中查询：溢出计数为 0 的分组会立即终止探测
+    let mut map = F14VectorMap::<usize, usize>::new().unwrap();
+    for i in 0..SIZE {
+        map.insert(i, i).unwrap();
     }
-     struct FixedHasherImpl(u64);
-    impl std::hash::Hasher for FixedHasherImpl {
-        fn write(&mut self, _: &[u8]) {}
-        fn finish(&self) -> u64 { self.0 }
+    
+    c.bench_function("f14_get_miss", |b| {
+        b.iter(|| {
+            for i in SIZE..SIZE * 2 {
+                black_box(map.get(&i));
+            }
+        })
+    });
+}
+
+fn bench_std_get_miss(c: &mut Criterion) {
+    let mut map = HashMap::new();
+    for i in 0..SIZE {
+        map.insert(i, i);
     }
-     let mut map: HashMap<usize, usize, FixedHasher> = 
-                HashMap::with_hasher(FixedHasher);
+    
+    c.bench_function("std_get_miss", |b| {
+        b.iter(|| {
+            for i in SIZE..SIZE * 2 {
+                black_box(map.get(&i));
+            }
+        })
+    });
+}
+
+fn bench_f14_remove(c: &mut Criterion) {
+    let mut map: F14VectorMap<usize, usize> = F14VectorMap::new().unwrap();
     for i in 0..SIZE {
         map.insert(i, i).unwrap();
     }
@@ -140,23 +121,7 @@ fn bench_std_remove(c: &mut Criterion) {
 }
 
 fn bench_f14_iter(c: &mut Criterion) {
-     #[derive(Clone)]
-    struct FixedHasher;
-    
-    impl std::hash::BuildHasher for FixedHasher {
-        type Hasher = FixedHasherImpl;
-        
-        fn build_hasher(&self) -> Self::Hasher {
-            FixedHasherImpl(0)
-        }
-    }
-     struct FixedHasherImpl(u64);
-    impl std::hash::Hasher for FixedHasherImpl {
-        fn write(&mut self, _: &[u8]) {}
-        fn finish(&self) -> u64 { self.0 }
-    }
-     let mut map: HashMap<usize, usize, FixedHasher> = 
-                HashMap::with_hasher(FixedHasher);
+    let mut map: F14VectorMap<usize, usize> = F14VectorMap::new().unwrap();
     for i in 0..SIZE {
         map.insert(i, i).unwrap();
     }
@@ -254,7 +219,7 @@ fn bench_std_high_collision(c: &mut Criterion) {
                 F14VectorMap::with_hasher(FixedHasher).unwrap();
             
             for i in 0..SIZE {
-                map.insert(i, i);
+                map.insert(i, i).unwrap();
             }
             
             for i in 0..SIZE {
@@ -304,23 +269,7 @@ fn bench_f14_rebuild(c: &mut Criterion) {
 }
 
 fn bench_f14_clear(c: &mut Criterion) {
-    #[derive(Clone)]
-    struct FixedHasher;
-    
-    impl std::hash::BuildHasher for FixedHasher {
-        type Hasher = FixedHasherImpl;
-        
-        fn build_hasher(&self) -> Self::Hasher {
-            FixedHasherImpl(0)
-        }
-    }
-     struct FixedHasherImpl(u64);
-    impl std::hash::Hasher for FixedHasherImpl {
-        fn write(&mut self, _: &[u8]) {}
-        fn finish(&self) -> u64 { self.0 }
-    }
-     let mut map: HashMap<usize, usize, FixedHasher> = 
-                HashMap::with_hasher(FixedHasher);
+    let mut map: F14VectorMap<usize, usize> = F14VectorMap::new().unwrap();
     for i in 0..SIZE {
         map.insert(i, i).unwrap();
     }
@@ -332,12 +281,112 @@ fn bench_f14_clear(c: &mut Criterion) {
     });
 }
 
+/// 批量查找与逐个查找的对比：键随机分布在远大于缓存的表中
+fn bench_f14_get_batch(c: &mut Criterion) {
+    const LARGE: usize = 1 << 20;
+    const BATCH: usize = 512;
+
+    let mut map: F14VectorMap<u64, u64> = F14VectorMap::new().unwrap();
+    for i in 0..LARGE as u64 {
+        map.insert(i, i).unwrap();
+    }
+    // 简单的乘法散列打乱访问顺序，包含约 1/8 的未命中键
+    let keys: Vec<u64> = (0..BATCH as u64)
+        .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) % (LARGE as u64 + LARGE as u64 / 8))
+        .collect();
+
+    let mut group = c.benchmark_group("f14_get_batch");
+    group.bench_function("loop_get", |b| {
+        b.iter(|| {
+            for key in &keys {
+                black_box(map.get(key));
+            }
+        })
+    });
+    group.bench_function("get_batch", |b| b.iter(|| black_box(map.get_batch(&keys))));
+    let mut out = vec![None; BATCH];
+    group.bench_function("get_batch_into", |b| {
+        b.iter(|| {
+            map.get_batch_into(&keys, &mut out);
+            black_box(&out);
+        })
+    });
+    group.finish();
+}
+
+/// 不同负载因子下的迭代：容量固定，元素越少表越稀疏
+fn bench_iter_load_factor(c: &mut Criterion) {
+    const CAPACITY: usize = 1 << 16;
+
+    let mut group = c.benchmark_group("iter_load_factor");
+    for percent in [5usize, 25, 50, 70] {
+        let len = CAPACITY * percent / 100;
+
+        let mut value_map: F14ValueMap<usize, usize> = F14ValueMap::with_capacity(CAPACITY).unwrap();
+        let mut vector_map: F14VectorMap<usize, usize> = F14VectorMap::with_capacity(CAPACITY).unwrap();
+        let mut std_map = HashMap::with_capacity(CAPACITY * 7 / 8);
+        for i in 0..len {
+            value_map.insert(i, i).unwrap();
+            vector_map.insert(i, i).unwrap();
+            std_map.insert(i, i);
+        }
+
+        group.bench_with_input(BenchmarkId::new("f14_value_iter", percent), &value_map, |b, map| {
+            b.iter(|| map.iter().map(|(_, value)| *value).sum::<usize>())
+        });
+        group.bench_with_input(BenchmarkId::new("f14_vector_iter", percent), &vector_map, |b, map| {
+            b.iter(|| map.iter().map(|(_, value)| *value).sum::<usize>())
+        });
+        group.bench_with_input(BenchmarkId::new("std_iter", percent), &std_map, |b, map| {
+            b.iter(|| map.values().sum::<usize>())
+        });
+    }
+    group.finish();
+}
+
+#[cfg(feature = "concurrent")]
+fn bench_concurrent_insert(c: &mut Criterion) {
+    // 写竞争：各线程插入互不相交的键，观察吞吐随线程数的变化
+    use f14vectormap::ConcurrentF14Map;
+    use std::{collections::hash_map::RandomState, thread};
+
+    const PER_THREAD: usize = 10_000;
+    let mut group = c.benchmark_group("concurrent_insert");
+    for threads in [1usize, 2, 4, 8] {
+        group.throughput(criterion::Throughput::Elements((threads * PER_THREAD) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, &threads| {
+            b.iter(|| {
+                let map = ConcurrentF14Map::<usize, usize>::with_shards_and_hasher(
+                    16,
+                    threads * PER_THREAD,
+                    RandomState::new(),
+                )
+                .unwrap();
+                thread::scope(|scope| {
+                    for t in 0..threads {
+                        let map = &map;
+                        scope.spawn(move || {
+                            for i in t * PER_THREAD..(t + 1) * PER_THREAD {
+                                map.insert(i, i).unwrap();
+                            }
+                        });
+                    }
+                });
+                black_box(map.len())
+            })
+        });
+    }
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_f14_insert,
     bench_std_insert,
     bench_f14_get,
     bench_std_get,
+    bench_f14_get_miss,
+    bench_std_get_miss,
     bench_f14_remove,
     bench_std_remove,
     bench_f14_iter,
@@ -345,6 +394,15 @@ criterion_group!(
     bench_f14_high_collision,
     bench_std_high_collision,
     bench_f14_rebuild,
-    bench_f14_clear
+    bench_f14_clear,
+    bench_f14_get_batch,
+    bench_iter_load_factor
 );
+
+#[cfg(feature = "concurrent")]
+criterion_group!(concurrent_benches, bench_concurrent_insert);
+
+#[cfg(feature = "concurrent")]
+criterion_main!(benches, concurrent_benches);
+#[cfg(not(feature = "concurrent"))]
 criterion_main!(benches);
\ No newline at end of file
@@ -1,42 +1,120 @@
 //! 定制内存分配器
+//!
+//! [`Allocator`] 是稳定版 Rust 上 `core::alloc::Allocator` 的替代，映射的分组表、
+//! 稠密数组与节点都通过它分配；默认的 [`AlignedAllocator`] 转发给全局分配器，
+//! 程序替换全局分配器时表内存也随之替换。
 
-use std::alloc::{GlobalAlloc, Layout, System};
+use std::alloc::{self, Layout};
+use std::fmt;
 use std::ptr::NonNull;
-use crate::simd_utils::SIMD_ALIGNMENT;
+use crate::{error::MapError, simd_utils::SIMD_ALIGNMENT};
 
-/// 对齐内存分配器
+/// 分配器无法满足请求
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "memory allocation failed")
+    }
+}
+
+impl std::error::Error for AllocError {}
+
+/// 内存分配器接口
+///
+/// 映射只会以非零大小的布局调用 `allocate`；分组表与稠密数组按
+/// [`SIMD_ALIGNMENT`] 对齐请求内存，节点按键值对自身的对齐请求。
+/// 同一映射内部可能持有分配器的多个克隆，任一克隆分配的内存都可能由另一克隆释放。
+///
+/// # Safety
+/// `allocate` 成功时返回的内存块必须满足 `layout` 的大小与对齐要求，并且在以相同
+/// `layout` 传给（该分配器或其克隆的）`deallocate` 之前一直有效。
+pub unsafe trait Allocator {
+    /// 分配满足 `layout` 的内存
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError>;
+
+    /// 释放内存
+    ///
+    /// # Safety
+    /// `ptr` 必须由本分配器（或其克隆）以相同的 `layout` 分配，且尚未被释放。
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+}
+
+unsafe impl<A: Allocator + ?Sized> Allocator for &A {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        (**self).allocate(layout)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { (**self).deallocate(ptr, layout) }
+    }
+}
+
+/// 按 SIMD 对齐分配 `size` 字节（内部使用），大小为零时返回悬垂指针
+pub(crate) fn alloc_simd_in<A: Allocator>(alloc: &A, size: usize) -> Result<NonNull<u8>, MapError> {
+    if size == 0 {
+        return Ok(NonNull::dangling());
+    }
+    let layout = Layout::from_size_align(size, SIMD_ALIGNMENT)
+        .map_err(|_| MapError::CapacityExceeded)?;
+    alloc
+        .allocate(layout)
+        .map_err(|_| MapError::AllocationFailed { layout })
+}
+
+/// 释放 [`alloc_simd_in`] 分配的内存（内部使用）
+///
+/// # Safety
+/// `ptr` 必须由 `alloc`（或其克隆）以相同的 `size` 经 [`alloc_simd_in`] 分配。
+pub(crate) unsafe fn dealloc_simd_in<A: Allocator>(alloc: &A, ptr: NonNull<u8>, size: usize) {
+    if size == 0 {
+        return;
+    }
+    let layout = Layout::from_size_align(size, SIMD_ALIGNMENT)
+        .expect("Invalid layout for deallocation");
+    unsafe { alloc.deallocate(ptr, layout) };
+}
+
+/// 对齐内存分配器，默认分配器
+///
+/// 转发给全局分配器；对齐要求由请求的布局决定。
+#[derive(Debug, Clone, Copy, Default)]
 pub struct AlignedAllocator;
 
+unsafe impl Allocator for AlignedAllocator {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        debug_assert!(layout.size() != 0);
+        NonNull::new(unsafe { alloc::alloc(layout) }).ok_or(AllocError)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { alloc::dealloc(ptr.as_ptr(), layout) };
+    }
+}
+
 impl AlignedAllocator {
     /// 分配对齐内存
-    pub unsafe fn alloc_aligned(size: usize) -> Result<NonNull<u8>, crate::error::MapError> {
-        if size == 0 {
-            return Ok(NonNull::dangling());
-        }
-        
-        // 创建布局
-        let layout = Layout::from_size_align(size, SIMD_ALIGNMENT)
-            .map_err(|_| crate::error::MapError::CapacityExceeded)?;
-        
-        // 分配内存
-        let ptr = unsafe { System.alloc(layout) };
-        if ptr.is_null() {
-            return Err(crate::error::MapError::CapacityExceeded);
-        }
-        
-        Ok(NonNull::new(ptr).unwrap())
+    ///
+    /// # Safety
+    /// 返回的内存未初始化，调用者须在读取前写入，并用相同的 `size`
+    /// 调用 [`AlignedAllocator::dealloc_aligned`] 释放。
+    pub unsafe fn alloc_aligned(size: usize) -> Result<NonNull<u8>, MapError> {
+        alloc_simd_in(&AlignedAllocator, size)
     }
     
     /// 释放对齐内存
+    ///
+    /// # Safety
+    /// `ptr` 必须由 [`AlignedAllocator::alloc_aligned`] 以相同的 `size` 分配，
+    /// 且尚未被释放。
     pub unsafe fn dealloc_aligned(ptr: *mut u8, size: usize) {
-        if size == 0 {
-            return;
+        if let Some(ptr) = NonNull::new(ptr) {
+            unsafe { dealloc_simd_in(&AlignedAllocator, ptr, size) };
         }
-        
-        let layout = Layout::from_size_align(size, SIMD_ALIGNMENT)
-            .expect("Invalid layout for deallocation");
-        unsafe { System.dealloc(ptr, layout) };
     }
 }
-
-//
\ No newline at end of file
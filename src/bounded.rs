@@ -0,0 +1,378 @@
+//! 有界映射：超出条目数或字节预算时驱逐冷数据
+//!
+//! [`BoundedF14Map`] 基于 [`F14VectorMap`]，每个键值对旁边附带一个 64 位的访问标记，
+//! 与键值对一起存放在稠密数组中。插入使总权重超出上限时，按 [`EvictionPolicy`]
+//! 选出冷条目移除，并在 [`Inserted::evicted`] 中返回，调用方可以据此记录日志。
+//!
+//! - [`EvictionPolicy::Clock`]：访问标记是一位引用位，时钟指针沿稠密数组循环扫描，
+//!   清除遇到的引用位，驱逐第一个引用位为零的条目。
+//! - [`EvictionPolicy::Lru`]：访问标记是最近一次访问的逻辑时间。
+//! - [`EvictionPolicy::Lfu`]：访问标记是访问次数。
+//!
+//! LRU 与 LFU 不维护全局有序结构，而是随机抽取 [`SAMPLES`] 个条目驱逐其中最冷的一个
+//! （与 Redis 的近似 LRU/LFU 相同）；条目数不超过抽样数时在全部条目中选择，结果精确。
+//!
+//! 权重由创建时给出的函数计算：按条目数限制时每个条目权重为 1，
+//! 按字节预算限制时由调用方估算每个键值对占用的字节数。每个条目记录写入时计入的
+//! 权重，移除、覆盖或驱逐时扣除记录的值，因此通过 [`BoundedF14Map::get_mut`]
+//! 修改值不会让总权重失准。
+
+use crate::{error::MapError, f14_map::F14VectorMap, traits::BuildHasherExt};
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    hash::Hash,
+    iter::FusedIterator,
+    mem, slice,
+};
+
+/// LRU/LFU 每次驱逐抽样的条目数
+pub const SAMPLES: usize = 8;
+
+/// 驱逐策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// 时钟（二次机会）算法
+    #[default]
+    Clock,
+    /// 最近最少使用（抽样近似）
+    Lru,
+    /// 最不经常使用（抽样近似）
+    Lfu,
+}
+
+/// 附带访问标记的值
+struct Tracked<V> {
+    value: V,
+    // 引用位 / 最近访问时间 / 访问次数，含义取决于驱逐策略
+    meta: u64,
+    // 写入时计入总权重的值
+    weight: usize,
+}
+
+/// [`BoundedF14Map::insert`] 的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inserted<K, V> {
+    /// 键已存在时被替换的旧值
+    pub previous: Option<V>,
+    /// 为腾出空间而被驱逐的键值对，按驱逐顺序排列
+    pub evicted: Vec<(K, V)>,
+}
+
+/// 超出上限时驱逐冷数据的有界映射
+pub struct BoundedF14Map<K, V, S = RandomState> {
+    map: F14VectorMap<K, Tracked<V>, S>,
+    policy: EvictionPolicy,
+    // 权重上限与当前总权重
+    max_weight: usize,
+    weight: usize,
+    weigher: fn(&K, &V) -> usize,
+    // 时钟指针（稠密数组下标）
+    hand: usize,
+    // LRU 的逻辑时间
+    tick: u64,
+    // 抽样用的 xorshift 状态
+    rng: u64,
+}
+
+/// 按条目数限制时每个条目的权重
+fn unit_weight<K, V>(_: &K, _: &V) -> usize {
+    1
+}
+
+impl<K, V, S> BoundedF14Map<K, V, S>
+where
+    S: BuildHasherExt + Default,
+{
+    /// 创建最多容纳 `max_entries` 个条目的有界映射
+    pub fn new(max_entries: usize, policy: EvictionPolicy) -> Result<Self, MapError> {
+        Self::with_hasher(max_entries, policy, S::default())
+    }
+
+    /// 创建总字节数不超过 `max_bytes` 的有界映射，`weigher` 估算每个键值对的字节数
+    pub fn with_byte_budget(
+        max_bytes: usize,
+        policy: EvictionPolicy,
+        weigher: fn(&K, &V) -> usize,
+    ) -> Result<Self, MapError> {
+        Self::with_byte_budget_and_hasher(max_bytes, policy, weigher, S::default())
+    }
+}
+
+impl<K, V, S> BoundedF14Map<K, V, S>
+where
+    S: BuildHasherExt,
+{
+    /// 使用指定的哈希构建器创建最多容纳 `max_entries` 个条目的有界映射
+    pub fn with_hasher(max_entries: usize, policy: EvictionPolicy, hasher: S) -> Result<Self, MapError> {
+        Self::with_byte_budget_and_hasher(max_entries, policy, unit_weight, hasher)
+    }
+
+    /// 使用指定的哈希构建器创建按字节预算限制的有界映射
+    pub fn with_byte_budget_and_hasher(
+        max_bytes: usize,
+        policy: EvictionPolicy,
+        weigher: fn(&K, &V) -> usize,
+        hasher: S,
+    ) -> Result<Self, MapError> {
+        Ok(Self {
+            map: F14VectorMap::with_hasher(hasher)?,
+            policy,
+            max_weight: max_bytes,
+            weight: 0,
+            weigher,
+            hand: 0,
+            tick: 0,
+            rng: 0x9E37_79B9_7F4A_7C15,
+        })
+    }
+
+    /// 驱逐策略
+    #[inline]
+    pub fn policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
+    /// 权重上限（条目数或字节数）
+    #[inline]
+    pub fn max_weight(&self) -> usize {
+        self.max_weight
+    }
+
+    /// 当前总权重
+    #[inline]
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    /// 元素数量
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// 检查是否为空
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// 迭代所有键值对（不更新访问标记）
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { inner: self.map.as_slice().iter() }
+    }
+
+    /// 清空映射
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.weight = 0;
+        self.hand = 0;
+    }
+
+    /// 新条目的初始访问标记
+    fn initial_meta(&mut self) -> u64 {
+        match self.policy {
+            // 新条目没有引用位，被访问过才能获得第二次机会
+            EvictionPolicy::Clock => 0,
+            EvictionPolicy::Lru => {
+                self.tick += 1;
+                self.tick
+            }
+            EvictionPolicy::Lfu => 1,
+        }
+    }
+
+    /// 记录一次访问
+    fn touch(policy: EvictionPolicy, tick: &mut u64, meta: &mut u64) {
+        match policy {
+            EvictionPolicy::Clock => *meta = 1,
+            EvictionPolicy::Lru => {
+                *tick += 1;
+                *meta = *tick;
+            }
+            EvictionPolicy::Lfu => *meta = meta.saturating_add(1),
+        }
+    }
+
+    /// 选出要驱逐的稠密数组下标，跳过 `protected`；除它之外没有条目时返回 `None`
+    fn victim(&mut self, protected: usize) -> Option<usize> {
+        let entries = self.map.as_mut_slice();
+        let len = entries.len();
+        if len <= 1 {
+            return None;
+        }
+
+        if self.policy == EvictionPolicy::Clock {
+            // 每轮清除遇到的引用位，最多两轮就能找到引用位为零的条目
+            loop {
+                if self.hand >= len {
+                    self.hand = 0;
+                }
+                let index = self.hand;
+                if index != protected {
+                    let meta = &mut entries[index].1.meta;
+                    if *meta == 0 {
+                        // 末尾元素会被移入该位置，指针停在这里继续检查它
+                        return Some(index);
+                    }
+                    *meta = 0;
+                }
+                self.hand += 1;
+            }
+        }
+
+        let colder = |best: Option<usize>, index: usize| match best {
+            _ if index == protected => best,
+            Some(best) if entries[best].1.meta <= entries[index].1.meta => Some(best),
+            _ => Some(index),
+        };
+        let mut best = None;
+        if len <= SAMPLES {
+            best = (0..len).fold(best, colder);
+        } else {
+            for _ in 0..SAMPLES {
+                // xorshift64
+                self.rng ^= self.rng << 13;
+                self.rng ^= self.rng >> 7;
+                self.rng ^= self.rng << 17;
+                best = colder(best, (self.rng % len as u64) as usize);
+            }
+        }
+        // 抽样全部落在受保护条目上时退回到相邻条目
+        Some(best.unwrap_or((protected + 1) % len))
+    }
+}
+
+impl<K, V, S> BoundedF14Map<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasherExt,
+{
+    /// 插入或覆盖键值对，返回旧值与被驱逐的条目
+    ///
+    /// 单个键值对的权重超过上限时返回 [`MapError::CapacityExceeded`]，映射保持不变。
+    /// 刚写入的条目不会被本次插入驱逐。
+    pub fn insert(&mut self, key: K, value: V) -> Result<Inserted<K, V>, MapError> {
+        let weight = (self.weigher)(&key, &value);
+        if weight > self.max_weight {
+            return Err(MapError::CapacityExceeded);
+        }
+
+        let (index, previous) = match self.map.index_of(&key) {
+            Some(index) => {
+                let tracked = &mut self.map.as_mut_slice()[index].1;
+                self.weight -= mem::replace(&mut tracked.weight, weight);
+                Self::touch(self.policy, &mut self.tick, &mut tracked.meta);
+                (index, Some(mem::replace(&mut tracked.value, value)))
+            }
+            None => {
+                let meta = self.initial_meta();
+                self.map.insert(key, Tracked { value, meta, weight })?;
+                // 向量布局把新键追加到稠密数组末尾
+                (self.map.len() - 1, None)
+            }
+        };
+        self.weight += weight;
+
+        Ok(Inserted { previous, evicted: self.evict_over_budget(index) })
+    }
+
+    /// 驱逐冷条目直到总权重不超过上限，或只剩下受保护的条目
+    fn evict_over_budget(&mut self, mut protected: usize) -> Vec<(K, V)> {
+        let mut evicted = Vec::new();
+        while self.weight > self.max_weight {
+            let Some(index) = self.victim(protected) else {
+                break;
+            };
+            let last = self.map.len() - 1;
+            let (key, tracked) = self.map.swap_remove_index(index);
+            if protected == last {
+                protected = index;
+            }
+            self.weight -= tracked.weight;
+            evicted.push((key, tracked.value));
+        }
+        evicted
+    }
+
+    /// 获取值的引用并记录一次访问
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    /// 获取值的可变引用并记录一次访问
+    ///
+    /// 通过返回的引用修改值不会重新计算权重，条目仍按写入时的权重计算，
+    /// 改变大小时请重新 [`insert`](Self::insert)。
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let tracked = self.map.get_mut(key)?;
+        Self::touch(self.policy, &mut self.tick, &mut tracked.meta);
+        Some(&mut tracked.value)
+    }
+
+    /// 获取值的引用，不记录访问
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).map(|tracked| &tracked.value)
+    }
+
+    /// 检查键是否存在，不记录访问
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// 移除键，返回值
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (_, tracked) = self.map.remove_entry(key)?;
+        self.weight -= tracked.weight;
+        Some(tracked.value)
+    }
+}
+
+impl<K: std::fmt::Debug, V: std::fmt::Debug, S: BuildHasherExt> std::fmt::Debug for BoundedF14Map<K, V, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// 键值对迭代器，由 [`BoundedF14Map::iter`] 创建
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, (K, Tracked<V>)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, tracked)| (key, &tracked.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
@@ -0,0 +1,288 @@
+//! 并发分片映射（需要启用 `concurrent` 特性）
+//!
+//! [`ConcurrentF14Map`] 按哈希高位把键分到 N 个分片，每个分片是一把读写锁保护的
+//! [`F14VectorMap`]。不同分片上的读写互不阻塞，同一分片上的读操作可以并行。
+//!
+//! 分片下标取片段（最高 7 位）之下的若干位：片段仍然在分片内部区分键，
+//! 低位继续用于选择分组，三者互不重叠。
+//!
+//! 写入者在持锁期间 panic 会使分片中毒，此后该分片上的写操作返回
+//! [`MapError::ConcurrentModification`]；读操作仍可继续观察分片内容。
+
+use crate::{
+    entry::Entry,
+    error::MapError,
+    f14_map::{make_hash, F14VectorMap},
+    traits::BuildHasherExt,
+};
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    hash::Hash,
+    iter::FusedIterator,
+    sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    thread, vec,
+};
+
+/// 片段占用的哈希高位数
+const FRAGMENT_BITS: u32 = 7;
+
+/// 最多支持的分片数
+const MAX_SHARDS: usize = 1 << 16;
+
+/// 并发分片哈希表
+pub struct ConcurrentF14Map<K, V, S = RandomState> {
+    // 分片，数量为 2 的幂
+    shards: Box<[RwLock<F14VectorMap<K, V, S>>]>,
+    // 分片下标位数
+    shard_bits: u32,
+    // 选择分片用的哈希构建器，各分片持有其克隆
+    hasher_builder: S,
+}
+
+/// 默认分片数：可用并行度的 4 倍，向上取整为 2 的幂
+fn default_shard_count() -> usize {
+    let parallelism = thread::available_parallelism().map_or(1, |n| n.get());
+    (parallelism * 4).next_power_of_two()
+}
+
+impl<K, V, S> ConcurrentF14Map<K, V, S>
+where
+    S: BuildHasherExt + Clone + Default,
+{
+    /// 创建一个新的并发映射，分片数按 CPU 数量选择
+    pub fn new() -> Result<Self, MapError> {
+        Self::with_capacity_and_hasher(0, S::default())
+    }
+
+    /// 创建总容量至少为 `capacity` 的并发映射
+    pub fn with_capacity(capacity: usize) -> Result<Self, MapError> {
+        Self::with_capacity_and_hasher(capacity, S::default())
+    }
+}
+
+impl<K, V, S> ConcurrentF14Map<K, V, S>
+where
+    S: BuildHasherExt + Clone,
+{
+    /// 使用指定的哈希构建器创建并发映射
+    pub fn with_hasher(hasher: S) -> Result<Self, MapError> {
+        Self::with_capacity_and_hasher(0, hasher)
+    }
+
+    /// 使用指定容量和哈希构建器创建并发映射
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Result<Self, MapError> {
+        Self::with_shards_and_hasher(default_shard_count(), capacity, hasher)
+    }
+
+    /// 指定分片数创建并发映射
+    ///
+    /// `shard_count` 向上取整为 2 的幂，`capacity` 平均分给各分片。
+    pub fn with_shards_and_hasher(shard_count: usize, capacity: usize, hasher: S) -> Result<Self, MapError> {
+        if shard_count > MAX_SHARDS {
+            return Err(MapError::CapacityExceeded);
+        }
+        let shard_count = shard_count.max(1).next_power_of_two();
+        let shard_capacity = capacity.div_ceil(shard_count);
+        let shards = (0..shard_count)
+            .map(|_| F14VectorMap::with_capacity_and_hasher(shard_capacity, hasher.clone()).map(RwLock::new))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            shards: shards.into_boxed_slice(),
+            shard_bits: shard_count.trailing_zeros(),
+            hasher_builder: hasher,
+        })
+    }
+
+    /// 分片数量
+    #[inline]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// 计算键的哈希及其所在的分片下标
+    ///
+    /// 各分片持有同一哈希构建器的克隆，分片内部直接复用这里算出的哈希。
+    fn locate<Q>(&self, key: &Q) -> (usize, u64)
+    where
+        Q: Hash + ?Sized,
+    {
+        let (full_hash, _) = make_hash(&self.hasher_builder, key);
+        if self.shard_bits == 0 {
+            return (0, full_hash);
+        }
+        (((full_hash << FRAGMENT_BITS) >> (64 - self.shard_bits)) as usize, full_hash)
+    }
+
+    /// 获取分片读锁，中毒的分片仍然可以读取
+    fn read_shard(&self, index: usize) -> RwLockReadGuard<'_, F14VectorMap<K, V, S>> {
+        self.shards[index].read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 获取分片写锁
+    fn write_shard(&self, index: usize) -> Result<RwLockWriteGuard<'_, F14VectorMap<K, V, S>>, MapError> {
+        self.shards[index]
+            .write()
+            .map_err(|_| MapError::ConcurrentModification)
+    }
+
+    /// 元素数量（逐个分片统计，并发写入时只是近似值）
+    pub fn len(&self) -> usize {
+        (0..self.shards.len()).map(|index| self.read_shard(index).len()).sum()
+    }
+
+    /// 检查是否为空
+    pub fn is_empty(&self) -> bool {
+        (0..self.shards.len()).all(|index| self.read_shard(index).is_empty())
+    }
+}
+
+impl<K, V, S> ConcurrentF14Map<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasherExt + Clone,
+{
+    /// 在持有分片读锁期间访问值
+    pub fn get_with<Q, R>(&self, key: &Q, f: impl FnOnce(&V) -> R) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (index, hash) = self.locate(key);
+        self.read_shard(index).get_with_hash(hash, key).map(f)
+    }
+
+    /// 获取值的克隆
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.get_with(key, V::clone)
+    }
+
+    /// 检查键是否存在
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_with(key, |_| ()).is_some()
+    }
+
+    /// 插入键值对，返回旧值
+    pub fn insert(&self, key: K, value: V) -> Result<Option<V>, MapError> {
+        let (index, hash) = self.locate(&key);
+        self.write_shard(index)?.insert_with_hash(hash, key, value)
+    }
+
+    /// 移除键，返回旧值
+    pub fn remove<Q>(&self, key: &Q) -> Result<Option<V>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (index, hash) = self.locate(key);
+        Ok(self.write_shard(index)?.remove_with_hash(hash, key))
+    }
+
+    /// 在持有分片写锁期间操作键对应的条目
+    ///
+    /// 锁只在 `f` 执行期间持有，`f` 中不能再访问同一映射，否则可能死锁。
+    pub fn entry<R>(&self, key: K, f: impl FnOnce(Entry<'_, K, V, S>) -> R) -> Result<R, MapError> {
+        let (index, hash) = self.locate(&key);
+        let mut shard = self.write_shard(index)?;
+        Ok(f(shard.try_entry_with_hash(hash, key)?))
+    }
+
+    /// 原子地根据旧值计算新值，返回旧值
+    ///
+    /// `f` 返回 `None` 时移除键。
+    pub fn compute<F>(&self, key: K, f: F) -> Result<Option<V>, MapError>
+    where
+        F: FnOnce(&K, Option<&V>) -> Option<V>,
+    {
+        self.entry(key, |entry| match entry {
+            Entry::Occupied(mut entry) => match f(entry.key(), Some(entry.get())) {
+                Some(value) => Ok(Some(entry.insert(value))),
+                None => Ok(Some(entry.remove())),
+            },
+            Entry::Vacant(entry) => match f(entry.key(), None) {
+                Some(value) => entry.try_insert_entry(value).map(|_| None),
+                None => Ok(None),
+            },
+        })?
+    }
+
+    /// 清空所有分片
+    pub fn clear(&self) -> Result<(), MapError> {
+        for index in 0..self.shards.len() {
+            self.write_shard(index)?.clear();
+        }
+        Ok(())
+    }
+
+    /// 某一时刻所有元素的快照
+    ///
+    /// 按下标顺序同时持有所有分片的读锁并克隆元素，得到一致的视图；
+    /// 返回的迭代器不再持有任何锁。
+    pub fn snapshot(&self) -> SnapshotIter<K, V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let guards: Vec<_> = (0..self.shards.len()).map(|index| self.read_shard(index)).collect();
+        let mut entries = Vec::with_capacity(guards.iter().map(|shard| shard.len()).sum());
+        for shard in &guards {
+            entries.extend(shard.iter().map(|(key, value)| (key.clone(), value.clone())));
+        }
+        SnapshotIter { inner: entries.into_iter() }
+    }
+}
+
+impl<K, V, S> std::fmt::Debug for ConcurrentF14Map<K, V, S>
+where
+    K: std::fmt::Debug,
+    V: std::fmt::Debug,
+    S: BuildHasherExt + Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut map = f.debug_map();
+        for index in 0..self.shards.len() {
+            map.entries(self.read_shard(index).iter());
+        }
+        map.finish()
+    }
+}
+
+/// 快照迭代器，由 [`ConcurrentF14Map::snapshot`] 创建
+pub struct SnapshotIter<K, V> {
+    inner: vec::IntoIter<(K, V)>,
+}
+
+impl<K, V> Iterator for SnapshotIter<K, V> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for SnapshotIter<K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for SnapshotIter<K, V> {}
+
+impl<K, V> FusedIterator for SnapshotIter<K, V> {}
@@ -0,0 +1,321 @@
+//! 连续存储的稠密数组
+//!
+//! F14VectorMap 的键值对按插入顺序紧密排列在这里，分组表只保存下标；
+//! 删除时用末尾元素填补空洞（swap-remove），数组始终没有空隙。
+
+use crate::{allocator::{self, Allocator}, error::MapError, simd_utils::SIMD_ALIGNMENT};
+use std::{
+    iter::FusedIterator, marker::PhantomData, mem, ptr::{self, NonNull}, slice
+};
+
+/// 固定容量的连续数组，内存按 SIMD 对齐通过分配器 `A` 分配
+pub(crate) struct DenseArray<T, A: Allocator> {
+    ptr: NonNull<T>,
+    capacity: usize,
+    len: usize,
+    alloc: A,
+    phantom: PhantomData<T>,
+}
+
+impl<T, A: Allocator> DenseArray<T, A> {
+    /// 创建不分配内存的空数组
+    pub(crate) fn new_in(alloc: A) -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            capacity: 0,
+            len: 0,
+            alloc,
+            phantom: PhantomData,
+        }
+    }
+
+    /// 创建容量恰好为 `capacity` 的数组
+    pub(crate) fn with_capacity_in(capacity: usize, alloc: A) -> Result<Self, MapError> {
+        let size = Self::alloc_size(capacity)?;
+        let ptr = Self::allocate(&alloc, size)?;
+
+        Ok(Self {
+            ptr,
+            capacity,
+            len: 0,
+            alloc,
+            phantom: PhantomData,
+        })
+    }
+
+    /// 计算分配大小
+    fn alloc_size(capacity: usize) -> Result<usize, MapError> {
+        if mem::align_of::<T>() > SIMD_ALIGNMENT {
+            return Err(MapError::CapacityExceeded);
+        }
+        capacity
+            .checked_mul(mem::size_of::<T>())
+            .ok_or(MapError::CapacityExceeded)
+    }
+
+    /// 获取容量
+    #[inline]
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 获取元素数量
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// 获取元素切片
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// 获取元素可变切片
+    #[inline]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// 在末尾追加元素，返回其下标
+    ///
+    /// # Panics
+    /// 数组已满时 panic（调用者负责在插入前扩容）。
+    #[inline]
+    pub(crate) fn push(&mut self, value: T) -> usize {
+        assert!(self.len < self.capacity, "DenseArray is full");
+        let index = self.len;
+        unsafe { ptr::write(self.ptr.as_ptr().add(index), value) };
+        self.len += 1;
+        index
+    }
+
+    /// 移除 `index` 处的元素并用末尾元素填补
+    ///
+    /// 返回被移除的元素；若发生了搬移，第二个值为末尾元素原来的下标。
+    pub(crate) fn swap_remove(&mut self, index: usize) -> (T, Option<usize>) {
+        assert!(index < self.len, "DenseArray index out of bounds");
+        let last = self.len - 1;
+        self.len = last;
+        unsafe {
+            let base = self.ptr.as_ptr();
+            let value = ptr::read(base.add(index));
+            if index == last {
+                (value, None)
+            } else {
+                ptr::copy_nonoverlapping(base.add(last), base.add(index), 1);
+                (value, Some(last))
+            }
+        }
+    }
+
+    /// 分配 `size` 字节，大小为零时返回按 `T` 对齐的悬垂指针
+    fn allocate(alloc: &A, size: usize) -> Result<NonNull<T>, MapError> {
+        if size == 0 {
+            return Ok(NonNull::dangling());
+        }
+        Ok(allocator::alloc_simd_in(alloc, size)?.cast())
+    }
+
+    /// 将所有元素移动到容量为 `capacity` 的新分配中
+    pub(crate) fn reallocate(&mut self, capacity: usize) -> Result<(), MapError> {
+        assert!(capacity >= self.len, "DenseArray capacity smaller than len");
+        let size = Self::alloc_size(capacity)?;
+        let new_ptr = Self::allocate(&self.alloc, size)?;
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr.as_ptr(), new_ptr.as_ptr(), self.len);
+        }
+        // 元素已搬走，旧分配只需释放
+        self.deallocate();
+        self.ptr = new_ptr;
+        self.capacity = capacity;
+        Ok(())
+    }
+
+    /// 克隆所有元素到容量相同的新数组
+    pub(crate) fn try_clone(&self) -> Result<Self, MapError>
+    where
+        T: Clone,
+        A: Clone,
+    {
+        let mut clone = Self::with_capacity_in(self.capacity, self.alloc.clone())?;
+        for value in self.as_slice() {
+            clone.push(value.clone());
+        }
+        Ok(clone)
+    }
+
+    /// 移出所有元素，保留内存
+    pub(crate) fn drain(&mut self) -> Drain<'_, T, A> {
+        let back = self.len;
+        // 先置零：即使迭代器被遗忘，数组也只是泄漏剩余元素
+        self.len = 0;
+        Drain { array: self, front: 0, back }
+    }
+
+    /// 元素起始指针
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub(crate) fn as_ptr(&self) -> NonNull<T> {
+        self.ptr
+    }
+
+    /// 丢弃所有元素但不析构，保留内存（元素必须已经移走，否则被泄漏）
+    #[cfg(feature = "rayon")]
+    pub(crate) fn forget_all(&mut self) {
+        self.len = 0;
+    }
+
+    /// 析构所有元素，保留内存
+    pub(crate) fn clear(&mut self) {
+        let len = self.len;
+        // 先置零，析构过程中 panic 也不会重复析构
+        self.len = 0;
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), len));
+        }
+    }
+
+    /// 释放内存（元素必须已经析构或移走）
+    fn deallocate(&mut self) {
+        let size = Self::alloc_size(self.capacity).expect("Invalid layout calculation");
+        unsafe { allocator::dealloc_simd_in(&self.alloc, self.ptr.cast(), size) };
+        self.capacity = 0;
+        self.ptr = NonNull::dangling();
+    }
+}
+
+// 数组独占其内存，线程安全性与元素和分配器一致
+unsafe impl<T: Send, A: Allocator + Send> Send for DenseArray<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for DenseArray<T, A> {}
+
+impl<T, A: Allocator> Drop for DenseArray<T, A> {
+    fn drop(&mut self) {
+        self.clear();
+        self.deallocate();
+    }
+}
+
+/// 按顺序移出元素的消耗迭代器
+pub struct IntoIter<T, A: Allocator> {
+    array: DenseArray<T, A>,
+    front: usize,
+    back: usize,
+}
+
+impl<T, A: Allocator> IntoIter<T, A> {
+    pub(crate) fn new(mut array: DenseArray<T, A>) -> Self {
+        let back = array.len;
+        // 所有权转移给迭代器，数组本身不再析构元素
+        array.len = 0;
+        Self { array, front: 0, back }
+    }
+
+    /// 剩余元素数量
+    #[inline]
+    pub(crate) fn remaining(&self) -> usize {
+        self.back - self.front
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        let value = unsafe { ptr::read(self.array.ptr.as_ptr().add(self.front)) };
+        self.front += 1;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining(), Some(self.remaining()))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(unsafe { ptr::read(self.array.ptr.as_ptr().add(self.back)) })
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
+    fn drop(&mut self) {
+        // 析构尚未取出的元素，内存由 `array` 释放
+        unsafe {
+            let remaining = slice::from_raw_parts_mut(
+                self.array.ptr.as_ptr().add(self.front),
+                self.back - self.front,
+            );
+            ptr::drop_in_place(remaining);
+        }
+    }
+}
+
+/// 移出所有元素的迭代器，由 [`DenseArray::drain`] 创建
+pub struct Drain<'a, T, A: Allocator> {
+    array: &'a mut DenseArray<T, A>,
+    front: usize,
+    back: usize,
+}
+
+impl<T, A: Allocator> Iterator for Drain<'_, T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        let value = unsafe { ptr::read(self.array.ptr.as_ptr().add(self.front)) };
+        self.front += 1;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for Drain<'_, T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(unsafe { ptr::read(self.array.ptr.as_ptr().add(self.back)) })
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for Drain<'_, T, A> {}
+
+impl<T, A: Allocator> FusedIterator for Drain<'_, T, A> {}
+
+impl<T, A: Allocator> Drop for Drain<'_, T, A> {
+    fn drop(&mut self) {
+        // 析构尚未取出的元素，内存留给数组
+        unsafe {
+            let remaining = slice::from_raw_parts_mut(
+                self.array.ptr.as_ptr().add(self.front),
+                self.back - self.front,
+            );
+            ptr::drop_in_place(remaining);
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! F14Map 条目（Entry）API
+//!
+//! 通过 [`F14Map::entry`] 获取，只计算一次哈希、只探测一次，
+//! 之后的读取、更新、插入或删除都直接复用探测到的槽位。
+//!
+//! 原始条目（[`F14Map::raw_entry`] / [`F14Map::raw_entry_mut`]）由调用方提供哈希和
+//! 相等判断闭包，适合哈希已在上游算好、或者不便构造键的场景。
+
+use crate::allocator::{AlignedAllocator, Allocator};
+use crate::error::MapError;
+use crate::f14_map::{split_hash, F14Map};
+use crate::policy::{StoragePolicy, VectorPolicy};
+use crate::traits::BuildHasherExt;
+use std::{borrow::Borrow, hash::Hash};
+
+/// 映射中某个键对应的条目
+pub enum Entry<'a, K, V, S, P: StoragePolicy = VectorPolicy, A: Allocator + Clone = AlignedAllocator> {
+    /// 键已存在
+    Occupied(OccupiedEntry<'a, K, V, S, P, A>),
+    /// 键不存在
+    Vacant(VacantEntry<'a, K, V, S, P, A>),
+}
+
+/// 已存在键的条目
+pub struct OccupiedEntry<'a, K, V, S, P: StoragePolicy = VectorPolicy, A: Allocator + Clone = AlignedAllocator> {
+    map: &'a mut F14Map<K, V, S, P, A>,
+    // 查找时传入的键（由 `entry` 创建时保留，供替换键使用）
+    key: Option<K>,
+    full_hash: u64,
+    index: usize,
+    fragment: u8,
+}
+
+/// 不存在键的条目，持有键以及已定位好的插入槽位
+pub struct VacantEntry<'a, K, V, S, P: StoragePolicy = VectorPolicy, A: Allocator + Clone = AlignedAllocator> {
+    map: &'a mut F14Map<K, V, S, P, A>,
+    key: K,
+    full_hash: u64,
+    index: usize,
+    fragment: u8,
+}
+
+impl<'a, K, V, S, P, A> Entry<'a, K, V, S, P, A>
+where
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 键不存在时插入默认值，返回值的可变引用
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// 键不存在时插入闭包生成的值，返回值的可变引用
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// 键不存在时用键生成值并插入，返回值的可变引用
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// 键存在时原地修改值
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+
+    /// 写入值（覆盖或插入），返回对应的已占用条目
+    pub fn insert_entry(self, value: V) -> OccupiedEntry<'a, K, V, S, P, A> {
+        match self {
+            Entry::Occupied(mut entry) => {
+                entry.insert(value);
+                entry
+            }
+            Entry::Vacant(entry) => entry.insert_entry(value),
+        }
+    }
+
+    /// 获取条目的键
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+}
+
+impl<'a, K, V: Default, S, P, A> Entry<'a, K, V, S, P, A>
+where
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 键不存在时插入 `V::default()`，返回值的可变引用
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V, S, P, A> OccupiedEntry<'a, K, V, S, P, A>
+where
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    pub(crate) fn new(
+        map: &'a mut F14Map<K, V, S, P, A>,
+        key: Option<K>,
+        full_hash: u64,
+        index: usize,
+        fragment: u8,
+    ) -> Self {
+        Self { map, key, full_hash, index, fragment }
+    }
+
+    /// 获取条目的键
+    pub fn key(&self) -> &K {
+        &self.map.pair_at(self.index).0
+    }
+
+    /// 获取值的引用
+    pub fn get(&self) -> &V {
+        &self.map.pair_at(self.index).1
+    }
+
+    /// 获取值的可变引用
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.pair_at_mut(self.index).1
+    }
+
+    /// 转换为与映射生命周期相同的值可变引用
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.pair_at_mut(self.index).1
+    }
+
+    /// 转换为与映射生命周期相同的键和值可变引用
+    pub fn into_key_value(self) -> (&'a K, &'a mut V) {
+        let (key, value) = self.map.pair_at_mut(self.index);
+        (&*key, value)
+    }
+
+    /// 替换值，返回旧值
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// 用查找时传入的键替换存储的键，返回旧键
+    ///
+    /// # Panics
+    /// 条目不是由 [`F14Map::entry`] 或 [`F14Map::try_entry`] 直接返回时 panic。
+    pub(crate) fn replace_key(self) -> K {
+        let key = self.key.expect("entry was not created with a lookup key");
+        std::mem::replace(&mut self.map.pair_at_mut(self.index).0, key)
+    }
+
+    /// 从映射中移除条目，返回值
+    pub fn remove(self) -> V
+    where
+        K: Hash,
+    {
+        self.remove_entry().1
+    }
+
+    /// 从映射中移除条目，返回键值对
+    pub fn remove_entry(self) -> (K, V)
+    where
+        K: Hash,
+    {
+        self.map.take_at(self.index, self.full_hash, self.fragment)
+    }
+}
+
+impl<'a, K, V, S, P, A> VacantEntry<'a, K, V, S, P, A>
+where
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    pub(crate) fn new(
+        map: &'a mut F14Map<K, V, S, P, A>,
+        key: K,
+        full_hash: u64,
+        index: usize,
+        fragment: u8,
+    ) -> Self {
+        Self { map, key, full_hash, index, fragment }
+    }
+
+    /// 获取将要插入的键
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// 取回键的所有权
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// 在已定位的槽位插入值，返回值的可变引用
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.insert_entry(value).into_mut()
+    }
+
+    /// 在已定位的槽位插入值，返回对应的已占用条目
+    ///
+    /// # Panics
+    /// 节点策略分配节点失败时 panic。
+    pub fn insert_entry(self, value: V) -> OccupiedEntry<'a, K, V, S, P, A> {
+        match self.try_insert_entry(value) {
+            Ok(entry) => entry,
+            Err(err) => panic!("VacantEntry::insert failed: {}", err),
+        }
+    }
+
+    /// 在已定位的槽位插入值，分配失败时返回错误且映射保持不变
+    pub(crate) fn try_insert_entry(self, value: V) -> Result<OccupiedEntry<'a, K, V, S, P, A>, MapError> {
+        let VacantEntry { map, key, full_hash, index, fragment } = self;
+        map.insert_at(index, key, value, full_hash, fragment)?;
+        Ok(OccupiedEntry::new(map, None, full_hash, index, fragment))
+    }
+}
+
+/// 只读原始条目构建器，由 [`F14Map::raw_entry`] 创建
+pub struct RawEntryBuilder<'a, K, V, S, P: StoragePolicy = VectorPolicy, A: Allocator + Clone = AlignedAllocator> {
+    map: &'a F14Map<K, V, S, P, A>,
+}
+
+/// 可修改的原始条目构建器，由 [`F14Map::raw_entry_mut`] 创建
+pub struct RawEntryBuilderMut<'a, K, V, S, P: StoragePolicy = VectorPolicy, A: Allocator + Clone = AlignedAllocator> {
+    map: &'a mut F14Map<K, V, S, P, A>,
+}
+
+/// 原始条目：已存在时复用 [`OccupiedEntry`]
+pub enum RawEntryMut<'a, K, V, S, P: StoragePolicy = VectorPolicy, A: Allocator + Clone = AlignedAllocator> {
+    /// 找到满足条件的键
+    Occupied(OccupiedEntry<'a, K, V, S, P, A>),
+    /// 没有满足条件的键
+    Vacant(RawVacantEntryMut<'a, K, V, S, P, A>),
+}
+
+/// 没有满足条件的键的原始条目，插入时由调用方提供键
+pub struct RawVacantEntryMut<'a, K, V, S, P: StoragePolicy = VectorPolicy, A: Allocator + Clone = AlignedAllocator> {
+    map: &'a mut F14Map<K, V, S, P, A>,
+    full_hash: u64,
+    fragment: u8,
+}
+
+impl<'a, K, V, S, P, A> RawEntryBuilder<'a, K, V, S, P, A>
+where
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    pub(crate) fn new(map: &'a F14Map<K, V, S, P, A>) -> Self {
+        Self { map }
+    }
+
+    /// 沿 `hash` 的探测序列查找满足 `is_match` 的键
+    pub fn from_hash<F>(self, hash: u64, is_match: F) -> Option<(&'a K, &'a V)>
+    where
+        F: FnMut(&K) -> bool,
+    {
+        let (full_hash, fragment) = split_hash(hash);
+        let slot = self.map.find_hashed(full_hash, fragment, is_match)?;
+        let (key, value) = self.map.pair_at(slot);
+        Some((key, value))
+    }
+
+    /// 使用预先计算的哈希按键查找（不校验哈希）
+    pub fn from_key_hashed_nocheck<Q>(self, hash: u64, key: &Q) -> Option<(&'a K, &'a V)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.from_hash(hash, |stored| stored.borrow() == key)
+    }
+}
+
+impl<'a, K, V, S, P, A> RawEntryBuilderMut<'a, K, V, S, P, A>
+where
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    pub(crate) fn new(map: &'a mut F14Map<K, V, S, P, A>) -> Self {
+        Self { map }
+    }
+
+    /// 沿 `hash` 的探测序列查找满足 `is_match` 的键
+    pub fn from_hash<F>(self, hash: u64, is_match: F) -> RawEntryMut<'a, K, V, S, P, A>
+    where
+        F: FnMut(&K) -> bool,
+    {
+        let (full_hash, fragment) = split_hash(hash);
+        match self.map.find_hashed(full_hash, fragment, is_match) {
+            Some(index) => RawEntryMut::Occupied(OccupiedEntry::new(self.map, None, full_hash, index, fragment)),
+            None => RawEntryMut::Vacant(RawVacantEntryMut { map: self.map, full_hash, fragment }),
+        }
+    }
+
+    /// 使用预先计算的哈希按键查找（不校验哈希）
+    pub fn from_key_hashed_nocheck<Q>(self, hash: u64, key: &Q) -> RawEntryMut<'a, K, V, S, P, A>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.from_hash(hash, |stored| stored.borrow() == key)
+    }
+}
+
+impl<'a, K, V, S, P, A> RawEntryMut<'a, K, V, S, P, A>
+where
+    K: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 不存在时插入闭包生成的键值对，返回存储的键和值
+    pub fn or_insert_with<F>(self, default: F) -> (&'a K, &'a mut V)
+    where
+        F: FnOnce() -> (K, V),
+    {
+        match self {
+            RawEntryMut::Occupied(entry) => entry.into_key_value(),
+            RawEntryMut::Vacant(entry) => {
+                let (key, value) = default();
+                entry.insert(key, value)
+            }
+        }
+    }
+}
+
+impl<'a, K, V, S, P, A> RawVacantEntryMut<'a, K, V, S, P, A>
+where
+    K: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 插入键值对，返回存储的键和值
+    ///
+    /// 键的哈希必须等于构建条目时给出的哈希。
+    ///
+    /// # Panics
+    /// 扩容或分配失败时 panic，需要处理错误时使用 [`RawVacantEntryMut::try_insert`]。
+    pub fn insert(self, key: K, value: V) -> (&'a K, &'a mut V) {
+        match self.try_insert(key, value) {
+            Ok(pair) => pair,
+            Err(err) => panic!("RawVacantEntryMut::insert failed: {}", err),
+        }
+    }
+
+    /// 插入键值对，扩容或分配失败时返回错误且映射保持不变
+    pub fn try_insert(self, key: K, value: V) -> Result<(&'a K, &'a mut V), MapError> {
+        let RawVacantEntryMut { map, full_hash, fragment } = self;
+        debug_assert_eq!(full_hash, map.hash_of(&key), "hash does not match key");
+        let slot = map.insert_unique_hashed(full_hash, fragment, key, value)?;
+        let (key, value) = map.pair_at_mut(slot);
+        Ok((&*key, value))
+    }
+}
@@ -1,12 +1,14 @@
 //! 映射错误类型定义
 
-use std::fmt;
+use std::{alloc::Layout, fmt};
 
 /// 映射操作可能发生的错误
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MapError {
     /// 超出最大容量限制
     CapacityExceeded,
+    /// 内存分配失败，映射保持失败前的状态
+    AllocationFailed { layout: Layout },
     /// SIMD 操作不支持
     UnsupportedSimd,
     /// 并发修改冲突
@@ -18,6 +20,12 @@ impl fmt::Display for MapError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             MapError::CapacityExceeded => write!(f, "Map capacity exceeded"),
+            MapError::AllocationFailed { layout } => write!(
+                f,
+                "Memory allocation failed (size {}, align {})",
+                layout.size(),
+                layout.align()
+            ),
             MapError::UnsupportedSimd => write!(f, "SIMD not supported on this platform"),
             MapError::ConcurrentModification => write!(f, "Concurrent modification detected"),
              MapError::InvalidSlotState => write!(f, "Invalid Slot State"),
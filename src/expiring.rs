@@ -0,0 +1,355 @@
+//! 按条目过期的映射
+//!
+//! [`ExpiringF14Map`] 基于 [`F14VectorMap`]，每个值旁边记录一个可选的截止时间。
+//! 查找时过期条目视为不存在，但不会立即回收（查找只持有共享引用）；
+//! [`ExpiringF14Map::expire_step`] 从上次停下的位置继续，每次最多检查给定数量的分组
+//! （每组 [`CHUNK_SIZE`] 个条目）并回收其中已过期的条目，调用方可以把清理
+//! 分摊到每个请求中，不会因一次性全表扫描而阻塞。
+//!
+//! 时间来自 [`Clock`]，测试中可以用 [`ManualClock`] 确定性地推进时间。
+
+use crate::{error::MapError, f14_map::F14VectorMap, simd_utils::CHUNK_SIZE, traits::BuildHasherExt};
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    hash::Hash,
+    iter::FusedIterator,
+    slice,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// 时间来源
+pub trait Clock {
+    /// 当前时间
+    fn now(&self) -> Instant;
+}
+
+/// 系统单调时钟
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// 手动推进的时钟，用于测试
+#[derive(Debug)]
+pub struct ManualClock {
+    origin: Instant,
+    // 相对 `origin` 的纳秒数
+    elapsed: AtomicU64,
+}
+
+impl ManualClock {
+    /// 以当前时刻为起点创建时钟
+    pub fn new() -> Self {
+        Self { origin: Instant::now(), elapsed: AtomicU64::new(0) }
+    }
+
+    /// 把时钟向前推进 `duration`
+    pub fn advance(&self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        self.elapsed.fetch_add(nanos, Ordering::Relaxed);
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.origin + Duration::from_nanos(self.elapsed.load(Ordering::Relaxed))
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    #[inline]
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    #[inline]
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+/// 附带截止时间的值
+struct Timed<V> {
+    value: V,
+    // `None` 表示永不过期
+    deadline: Option<Instant>,
+}
+
+impl<V> Timed<V> {
+    #[inline]
+    fn is_live(&self, now: Instant) -> bool {
+        self.deadline.is_none_or(|deadline| now < deadline)
+    }
+}
+
+/// 条目可以过期的映射
+pub struct ExpiringF14Map<K, V, S = RandomState, C = SystemClock> {
+    map: F14VectorMap<K, Timed<V>, S>,
+    clock: C,
+    // 未显式指定时使用的存活时间，`None` 表示永不过期
+    default_ttl: Option<Duration>,
+    // 增量清理的下一个稠密数组下标
+    cursor: usize,
+}
+
+impl<K, V, S, C> ExpiringF14Map<K, V, S, C>
+where
+    S: BuildHasherExt + Default,
+    C: Clock + Default,
+{
+    /// 创建一个新的映射，条目默认永不过期
+    pub fn new() -> Result<Self, MapError> {
+        Self::with_hasher_and_clock(S::default(), C::default())
+    }
+}
+
+impl<K, V, S, C> ExpiringF14Map<K, V, S, C>
+where
+    S: BuildHasherExt + Default,
+    C: Clock,
+{
+    /// 使用指定的时钟创建映射
+    pub fn with_clock(clock: C) -> Result<Self, MapError> {
+        Self::with_hasher_and_clock(S::default(), clock)
+    }
+}
+
+impl<K, V, S, C> ExpiringF14Map<K, V, S, C>
+where
+    S: BuildHasherExt,
+    C: Clock,
+{
+    /// 使用指定的哈希构建器和时钟创建映射
+    pub fn with_hasher_and_clock(hasher: S, clock: C) -> Result<Self, MapError> {
+        Ok(Self { map: F14VectorMap::with_hasher(hasher)?, clock, default_ttl: None, cursor: 0 })
+    }
+
+    /// [`insert`](Self::insert) 使用的默认存活时间
+    #[inline]
+    pub fn default_ttl(&self) -> Option<Duration> {
+        self.default_ttl
+    }
+
+    /// 设置默认存活时间，只影响之后的插入
+    pub fn set_default_ttl(&mut self, ttl: Option<Duration>) {
+        self.default_ttl = ttl;
+    }
+
+    /// 时钟
+    #[inline]
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// 条目数量，包括已过期但尚未回收的条目
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// 检查是否为空（已过期但尚未回收的条目也计入）
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// 迭代未过期的键值对
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { inner: self.map.as_slice().iter(), now: self.clock.now() }
+    }
+
+    /// 清空映射
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.cursor = 0;
+    }
+}
+
+impl<K, V, S, C> ExpiringF14Map<K, V, S, C>
+where
+    K: Eq + Hash,
+    S: BuildHasherExt,
+    C: Clock,
+{
+    /// 按默认存活时间插入键值对，返回未过期的旧值
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, MapError> {
+        let deadline = self.default_ttl.and_then(|ttl| self.deadline_after(ttl));
+        self.insert_timed(key, value, deadline)
+    }
+
+    /// 插入存活 `ttl` 后过期的键值对，返回未过期的旧值
+    ///
+    /// 截止时间超出 [`Instant`] 的表示范围时（例如 `Duration::MAX`）条目永不过期。
+    pub fn insert_with_ttl(&mut self, key: K, value: V, ttl: Duration) -> Result<Option<V>, MapError> {
+        let deadline = self.deadline_after(ttl);
+        self.insert_timed(key, value, deadline)
+    }
+
+    /// 从现在起经过 `ttl` 的截止时间，溢出时视为没有截止时间
+    fn deadline_after(&self, ttl: Duration) -> Option<Instant> {
+        self.clock.now().checked_add(ttl)
+    }
+
+    /// 插入在 `deadline` 过期的键值对，返回未过期的旧值
+    pub fn insert_with_deadline(&mut self, key: K, value: V, deadline: Instant) -> Result<Option<V>, MapError> {
+        self.insert_timed(key, value, Some(deadline))
+    }
+
+    /// 插入永不过期的键值对，返回未过期的旧值
+    pub fn insert_persistent(&mut self, key: K, value: V) -> Result<Option<V>, MapError> {
+        self.insert_timed(key, value, None)
+    }
+
+    fn insert_timed(&mut self, key: K, value: V, deadline: Option<Instant>) -> Result<Option<V>, MapError> {
+        let now = self.clock.now();
+        let previous = self.map.insert(key, Timed { value, deadline })?;
+        Ok(previous.filter(|timed| timed.is_live(now)).map(|timed| timed.value))
+    }
+
+    /// 获取未过期的值
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let now = self.clock.now();
+        self.map.get(key).filter(|timed| timed.is_live(now)).map(|timed| &timed.value)
+    }
+
+    /// 获取未过期值的可变引用
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let now = self.clock.now();
+        self.map.get_mut(key).filter(|timed| timed.is_live(now)).map(|timed| &mut timed.value)
+    }
+
+    /// 检查键是否存在且未过期
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// 剩余存活时间，永不过期的条目返回 `Some(None)`，不存在或已过期返回 `None`
+    pub fn ttl<Q>(&self, key: &Q) -> Option<Option<Duration>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let now = self.clock.now();
+        let timed = self.map.get(key).filter(|timed| timed.is_live(now))?;
+        Some(timed.deadline.map(|deadline| deadline - now))
+    }
+
+    /// 移除键，返回未过期的值（已过期的条目同样被回收）
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let now = self.clock.now();
+        self.map.remove(key).filter(|timed| timed.is_live(now)).map(|timed| timed.value)
+    }
+
+    /// 增量回收过期条目：最多检查 `budget` 个分组，返回回收的条目数
+    ///
+    /// 从上次停下的位置继续，到达末尾后回到开头，重复调用最终会覆盖所有条目。
+    pub fn expire_step(&mut self, budget: usize) -> usize {
+        let now = self.clock.now();
+        let mut checked = 0;
+        let mut expired = 0;
+        let limit = budget.saturating_mul(CHUNK_SIZE);
+
+        while checked < limit && !self.map.is_empty() {
+            if self.cursor >= self.map.len() {
+                self.cursor = 0;
+            }
+            checked += 1;
+            if self.map.as_slice()[self.cursor].1.is_live(now) {
+                self.cursor += 1;
+            } else {
+                // 末尾条目被移入当前位置，游标不动，下一轮检查它
+                self.map.swap_remove_index(self.cursor);
+                expired += 1;
+            }
+        }
+        expired
+    }
+
+    /// 回收全部过期条目，返回回收的条目数
+    pub fn expire_all(&mut self) -> usize {
+        let now = self.clock.now();
+        let mut expired = 0;
+        let mut index = 0;
+        while index < self.map.len() {
+            if self.map.as_slice()[index].1.is_live(now) {
+                index += 1;
+            } else {
+                self.map.swap_remove_index(index);
+                expired += 1;
+            }
+        }
+        self.cursor = 0;
+        expired
+    }
+}
+
+impl<K, V, S, C> std::fmt::Debug for ExpiringF14Map<K, V, S, C>
+where
+    K: std::fmt::Debug,
+    V: std::fmt::Debug,
+    S: BuildHasherExt,
+    C: Clock,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// 未过期键值对的迭代器，由 [`ExpiringF14Map::iter`] 创建
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, (K, Timed<V>)>,
+    // 创建迭代器时的时间，迭代期间不再读取时钟
+    now: Instant,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let now = self.now;
+        self.inner
+            .find(|(_, timed)| timed.is_live(now))
+            .map(|(key, timed)| (key, &timed.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
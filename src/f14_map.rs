@@ -1,827 +1,1205 @@
-//! F14VectorMap 核心实现
+//! F14Map 核心实现
+//!
+//! 哈希、探测、扩容与条目逻辑在这里实现一次，键值对的存放方式由
+//! [`StoragePolicy`] 类型参数决定（见 [`crate::policy`]）。默认的向量策略
+//! （对应 Folly F14Vector）把键值对按插入顺序紧密存放在连续数组中，
+//! 分组表的槽位只保存 32 位下标，迭代就是一次连续的切片遍历。
+//! 所有内存都通过分配器类型参数 `A` 分配（见 [`crate::allocator`]）。
 
-use crate::{dispatch_simd, traits::HasherExt};
-use std::println as info; // 使用 info! 宏替代 println!
+use crate::traits::HasherExt;
 use super::{
-    simd_utils::{self, CHUNK_SIZE, EMPTY, DELETED, FULL_MASK},
+    allocator::{AlignedAllocator, Allocator},
+    simd_utils::{self, ChunkMeta, CHUNK_SIZE},
     error::MapError,
     traits::BuildHasherExt,
-    iterators::{Iter, IterMut, IntoIter},
-    allocator::AlignedAllocator,
+    iterators::{Drain, ExtractIf, IntoIter, IntoKeys, IntoValues, Iter, IterMut, Keys, Values, ValuesMut},
+    entry::{Entry, OccupiedEntry, RawEntryBuilder, RawEntryBuilderMut, VacantEntry},
+    policy::{FastPolicy, NodePolicy, StoragePolicy, ValuePolicy, VectorPolicy},
+    storage::RawStorage,
+    raw_table::capacity_for,
 };
 use std::{
-    borrow::Borrow, hash::{ Hash}, marker::PhantomData, mem::{self, MaybeUninit}, ptr::{self, NonNull}
+    borrow::Borrow, collections::hash_map::RandomState, hash::{ Hash}
 };
-const MAX_CAPACITY: usize = usize::MAX / (CHUNK_SIZE * 2);
-/// 槽位状态
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum SlotState {
-    Empty,
-    Deleted,
-    Full,
-}
 
-/// 键值对存储单元
-#[repr(C)]
-pub(crate) struct KeyValuePair<K, V> {
-   pub key: MaybeUninit<K>,
-   pub  value: MaybeUninit<V>,
-}
+pub use crate::raw_table::SlotState;
 
-/// F14VectorMap 主结构
-pub struct F14VectorMap<K, V, S = std::collections::hash_map::RandomState> 
+/// [`F14Map::get_batch`] 每批先哈希并预取的键数量
+pub const BATCH_PREFETCH: usize = 16;
+
+/// F14 哈希表主结构，`P` 为存储策略，`A` 为内存分配器
+pub struct F14Map<K, V, S = RandomState, P = VectorPolicy, A = AlignedAllocator>
 where
     K: Sized,  // 在结构体级别添加约束
-    V: Sized,  
+    V: Sized,
+    P: StoragePolicy,
+    A: Allocator + Clone,
 {
-    // 控制字节数组
-    ctrls: NonNull<u8>,
-    // 键值对数组
-    entries: NonNull<KeyValuePair<K, V>>,
-    // 容量（总槽位数）
-    capacity: usize,
-    // 分组数
-    group_count: usize,
-    // 有效元素数量
-    len: usize,
-    // 删除标记数量
-    deleted: usize,
+    // 分组表与键值对存储
+    storage: P::Storage<K, V, A>,
     // 哈希构建器
     hasher_builder: S,
-    // 标记类型关系
-    phantom: PhantomData<(K, V)>,
+    // 批量更新版本号，每次成功应用批量更新加一，其他修改不改变它
+    batch_version: u64,
 }
 
-impl<K, V, S> F14VectorMap<K, V, S> {
+/// [`F14Map::apply_batch`] 的执行结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchReport {
+    /// 新插入的键数量
+    pub inserted: usize,
+    /// 覆盖已有值的键数量
+    pub updated: usize,
+    /// 删除的键数量
+    pub removed: usize,
+    /// 要删除但不存在的键数量
+    pub missing: usize,
+    /// 应用后映射的批量更新版本号
+    pub batch_version: u64,
+}
+
+/// 向量策略映射：键值对连续存放，分组表保存下标
+pub type F14VectorMap<K, V, S = RandomState, A = AlignedAllocator> = F14Map<K, V, S, VectorPolicy, A>;
+
+/// 值策略映射：键值对直接存放在分组表中
+pub type F14ValueMap<K, V, S = RandomState, A = AlignedAllocator> = F14Map<K, V, S, ValuePolicy, A>;
+
+/// 节点策略映射：键值对单独分配，扩容不移动键值对
+pub type F14NodeMap<K, V, S = RandomState, A = AlignedAllocator> = F14Map<K, V, S, NodePolicy, A>;
+
+/// 快速映射：按键值对大小选择值策略或向量策略
+pub type F14FastMap<K, V, S = RandomState, A = AlignedAllocator> = F14Map<K, V, S, FastPolicy, A>;
+
+/// 用哈希构建器计算键的哈希和片段
+#[inline]
+pub(crate) fn make_hash<S, Q>(hasher_builder: &S, key: &Q) -> (u64, u8)
+where
+    S: BuildHasherExt,
+    Q: Hash + ?Sized,
+{
+    let mut hasher = hasher_builder.build_hasher_ext();
+    key.hash(&mut hasher);
+    let (full_hash, fragment) = hasher.finish_ext();
+
+    // 确保片段在有效范围内，高位为0表示FULL
+    let fragment = simd_utils::make_ctrl_byte(fragment);
+    (full_hash, fragment)
+}
+
+/// 由完整哈希得到片段：取最高 7 位（与 [`make_hash`] 一致）
+#[inline]
+pub(crate) fn split_hash(full_hash: u64) -> (u64, u8) {
+    (full_hash, simd_utils::make_ctrl_byte((full_hash >> (64 - 7)) as u8))
+}
+
+impl<K, V, S, P: StoragePolicy, A: Allocator + Clone> F14Map<K, V, S, P, A> {
+    /// 获取映射使用的分配器
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.storage.allocator()
+    }
+
+    /// 获取映射使用的哈希构建器
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        &self.hasher_builder
+    }
+
+    /// 批量更新版本号，每次成功的 [`F14Map::apply_batch`] 加一
+    ///
+    /// 只统计批量更新：`insert`、`remove`、`entry`、`retain`、`clear` 等单独的修改
+    /// 都不改变它，因此不能用来判断映射自某一时刻以来是否被修改过。
+    #[inline]
+    pub fn batch_version(&self) -> u64 {
+        self.batch_version
+    }
+
     /// 获取每组的槽位数
     #[inline]
     pub fn chunk_size(&self) -> usize {
         CHUNK_SIZE
     }
-    
+
     /// 获取分组数量
     #[inline]
     pub fn group_count(&self) -> usize {
-        self.group_count
+        self.storage.group_count()
     }
-    
+
     /// 获取容量
     #[inline]
     pub fn capacity(&self) -> usize {
-        self.capacity
+        self.storage.capacity()
+    }
+
+    /// 下一次插入新键是否会触发扩容（内部使用）
+    #[inline]
+    pub(crate) fn needs_grow(&self) -> bool {
+        self.storage.needs_grow()
     }
-    
+
     /// 获取元素数量
     #[inline]
     pub fn len(&self) -> usize {
-        self.len
+        self.storage.len()
     }
-     /// 获取删除标记数量
+
+    /// 获取删除标记数量
+    ///
+    /// 删除操作依靠溢出计数直接把槽位恢复为 EMPTY，不会产生墓碑；
+    /// 只有通过 [`F14Map::replace_slot_state`] 显式写入 `Deleted` 时才会计数。
     pub fn deleted_count(&self) -> usize {
-        self.deleted
+        self.storage.deleted()
     }
+
     /// 检查是否为空
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.len == 0
+        self.len() == 0
     }
-    
-    /// 获取控制字节指针
+
+    /// 获取指定分组的溢出元数据
     #[inline]
-    fn ctrls_ptr(&self) -> *mut u8 {
-        self.ctrls.as_ptr()
+    pub fn chunk_meta(&self, group_index: usize) -> ChunkMeta {
+        self.storage.chunk_meta(group_index)
     }
-    
-    /// 获取键值对指针
+
+    /// 获取槽位状态
+    pub fn slot_state(&self, index: usize) -> SlotState {
+        self.storage.slot_state(index)
+    }
+
+    /// 获取分组槽位对应的键值对 (内部使用)
     #[inline]
-   fn entries_ptr(&self) -> *mut KeyValuePair<K, V> {
-        self.entries.as_ptr()
+    pub(crate) fn pair_at(&self, slot: usize) -> &(K, V) {
+        self.storage.pair(slot)
     }
-    /// 获取指定索引的键值对引用 (内部使用)
+
+    /// 获取分组槽位对应的键值对可变引用 (内部使用)
     #[inline]
-    pub(crate)  fn get_entry(&self, index: usize) -> &KeyValuePair<K, V> {
-        unsafe { &*self.entries_ptr().add(index) }
+    pub(crate) fn pair_at_mut(&mut self, slot: usize) -> &mut (K, V) {
+        self.storage.pair_mut(slot)
     }
-    
-    /// 获取指定索引的键值对可变引用 (内部使用)
+
+    /// 获取迭代器
+    ///
+    /// 向量策略按插入顺序（删除会把末尾元素移入空洞）遍历，其他策略按槽位顺序遍历。
+    pub fn iter(&self) -> Iter<'_, K, V, P, A> {
+        Iter::new(self.storage.iter())
+    }
+
+    /// 获取可变迭代器
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V, P, A> {
+        IterMut::new(self.storage.iter_mut())
+    }
+
+    /// 获取消耗迭代器
+    ///
+    /// 与 [`IntoIterator::into_iter`] 相同，保留固有方法以兼容直接调用 `map.into_iter()` 的代码。
+    #[allow(clippy::should_implement_trait)]
+    pub fn into_iter(self) -> IntoIter<K, V, P, A> {
+        IntoIter::new(self.storage.into_iter())
+    }
+
+    /// 按存储顺序迭代键
+    pub fn keys(&self) -> Keys<'_, K, V, P, A> {
+        Keys::new(self.iter())
+    }
+
+    /// 按存储顺序迭代值
+    pub fn values(&self) -> Values<'_, K, V, P, A> {
+        Values::new(self.iter())
+    }
+
+    /// 按存储顺序迭代值的可变引用
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V, P, A> {
+        ValuesMut::new(self.iter_mut())
+    }
+
+    /// 消耗映射，按存储顺序移出键
+    pub fn into_keys(self) -> IntoKeys<K, V, P, A> {
+        IntoKeys::new(self.into_iter())
+    }
+
+    /// 消耗映射，按存储顺序移出值
+    pub fn into_values(self) -> IntoValues<K, V, P, A> {
+        IntoValues::new(self.into_iter())
+    }
+
+    /// 公共 clear 方法
+    pub fn clear(&mut self) {
+        self.storage.clear();
+    }
+
+    /// 克隆映射，失败时返回错误
+    ///
+    /// 新映射与原映射容量相同，控制字节与溢出元数据直接复制，键值对逐个克隆，
+    /// 不重新计算哈希。
+    pub fn try_clone(&self) -> Result<Self, MapError>
+    where
+        K: Clone,
+        V: Clone,
+        S: Clone,
+    {
+        Ok(F14Map {
+            storage: self.storage.try_clone()?,
+            hasher_builder: self.hasher_builder.clone(),
+            batch_version: self.batch_version,
+        })
+    }
+
+    /// 移出所有键值对，保留已分配的内存
+    ///
+    /// 迭代器析构时析构尚未取出的键值对；即使迭代器被遗忘，映射也会是空的。
+    pub fn drain(&mut self) -> Drain<'_, K, V, P, A> {
+        Drain::new(self.storage.drain())
+    }
+
+    /// 分组中 FULL 槽位的位掩码 (内部使用)
     #[inline]
-    pub(crate)   fn get_entry_mut(&mut self, index: usize) -> &mut KeyValuePair<K, V> {
-        unsafe { &mut *self.entries_ptr().add(index) }
+    pub(crate) fn full_mask(&self, group_index: usize) -> u16 {
+        self.storage.full_mask(group_index)
     }
-    /// 获取指定索引的控制字节
+
+    /// 底层存储，供并行迭代按分组拆分 (内部使用)
+    #[cfg(feature = "rayon")]
     #[inline]
-    fn get_ctrl(&self, index: usize) -> u8 {
-        unsafe { *self.ctrls_ptr().add(index) }
+    pub(crate) fn storage(&self) -> &P::Storage<K, V, A> {
+        &self.storage
     }
-    
-    /// 设置指定索引的控制字节
+
+    /// 底层存储（可变），供并行迭代按分组拆分 (内部使用)
+    #[cfg(feature = "rayon")]
     #[inline]
-    fn set_ctrl(&mut self, index: usize, ctrl: u8) {
-        unsafe { *self.ctrls_ptr().add(index) = ctrl }
+    pub(crate) fn storage_mut(&mut self) -> &mut P::Storage<K, V, A> {
+        &mut self.storage
     }
-    
-    /// 获取槽位状态
-    pub fn slot_state(&self, index: usize) -> SlotState {
-        let ctrl = self.get_ctrl(index);
-        match ctrl {
-            EMPTY => SlotState::Empty,
-            DELETED => SlotState::Deleted,
-            _ if ctrl & FULL_MASK == ctrl => SlotState::Full,
-            _ => SlotState::Deleted, // 无效状态处理为Deleted
-        }
+}
+
+impl<K, V, S, A: Allocator + Clone> F14Map<K, V, S, VectorPolicy, A> {
+    /// 以切片形式访问所有键值对（按插入顺序，删除会把末尾元素移入空洞）
+    #[inline]
+    pub fn as_slice(&self) -> &[(K, V)] {
+        self.storage.as_slice()
+    }
+
+    /// 以可变切片形式访问所有键值对（内部使用，调用方不得修改键）
+    #[inline]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [(K, V)] {
+        self.storage.as_mut_slice()
     }
-    
-     /// 减少长度（内部使用）
-   pub fn decrement_len(&mut self) {
-        self.len -= 1;
+}
+
+impl<K, V, S, A> F14Map<K, V, S, VectorPolicy, A>
+where
+    K: Eq + Hash,
+    S: BuildHasherExt,
+    A: Allocator + Clone,
+{
+    /// 键在稠密数组中的下标（内部使用）
+    pub(crate) fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.lookup(key).map(|slot| self.storage.index_at(slot))
     }
-    
-    /// 替换槽位状态并返回旧数据（内部使用）
-    pub unsafe fn replace_slot_state(&mut self, index: usize, new_state: SlotState) -> (K, V) {
-        // 保存旧状态
-        let old_ctrl = self.get_ctrl(index);
-        let old_state = match old_ctrl {
-            EMPTY => SlotState::Empty,
-            DELETED => SlotState::Deleted,
-            _ => SlotState::Full,
-        };
-        
-        // 设置新控制字节
-        let new_ctrl = match new_state {
-            SlotState::Empty => EMPTY,
-            SlotState::Deleted => DELETED,
-            SlotState::Full => {0},
-        };
-        self.set_ctrl(index, new_ctrl);
-        
-        // 获取槽位数据
-        unsafe {
-        //let entry = self.get_entry_mut(index);
-        //let key = ptr::read(entry.key.as_ptr());
-        //let value = ptr::read(entry.value.as_ptr());
-         // 获取槽位数据
-    let entry_ptr = self.entries_ptr().add(index);
-    let key = ptr::read(&(*entry_ptr).key as *const _ as *const K);
-    let value = ptr::read(&(*entry_ptr).value as *const _ as *const V);
-        // 如果从FULL状态变为非FULL状态，减少长度
-        if matches!(old_state, SlotState::Full) && !matches!(new_state, SlotState::Full) {
-            self.len -= 1;
-        }
-        
-        (key, value)
-        }
-        
+
+    /// 移除稠密数组下标 `index` 处的键值对，末尾元素移入该位置（内部使用）
+    pub(crate) fn swap_remove_index(&mut self, index: usize) -> (K, V) {
+        self.swap_remove_index_hashed(index).1
     }
 
-     /// 计算内存布局
-    /// 计算内存布局
-    fn calculate_layout(capacity: usize) -> Result<usize, MapError> {
-        if capacity == 0 {
-            return Ok(0);
-        }
-        
-        // 计算控制字节大小
-        let ctrls_size = capacity * mem::size_of::<u8>();
-        // 计算键值对大小
-        let entries_size = capacity * mem::size_of::<KeyValuePair<K, V>>();
-        
-        // 总大小
-        let total_size = ctrls_size + entries_size;
-        
-        Ok(total_size)
-    }
-    
-    /// 分配内存
-    unsafe fn allocate(capacity: usize) -> Result<(NonNull<u8>, NonNull<KeyValuePair<K, V>>), MapError> {
-        if capacity == 0 {
-            return Ok((
-                NonNull::dangling(),
-                NonNull::dangling(),
-            ));
+    /// 同 [`swap_remove_index`](Self::swap_remove_index)，并返回键的哈希，
+    /// 供调用方原样写入另一张共享哈希构建器的表（内部使用）
+    pub(crate) fn swap_remove_index_hashed(&mut self, index: usize) -> (u64, (K, V)) {
+        let (full_hash, fragment) = self.hash_key(&self.as_slice()[index].0);
+        let slot = self
+            .storage
+            .find_index(full_hash, fragment, index)
+            .expect("dense index must be referenced by the table");
+        (full_hash, self.take_at(slot, full_hash, fragment))
+    }
+
+    /// 插入调用方保证不存在的键，不重建、不扩容也不分配内存（内部使用）
+    ///
+    /// 调用方需保证表未达到负载上限，此时分组表一定有空闲槽位。
+    pub(crate) fn insert_unique_no_grow(&mut self, hash: u64, key: K, value: V) {
+        debug_assert!(!self.needs_grow(), "table is at its load limit");
+        let (full_hash, fragment) = split_hash(hash);
+        let slot = self
+            .storage
+            .find_insert_slot(full_hash, fragment)
+            .expect("table below its load limit has a free slot");
+        self.storage.push_at(slot, full_hash, fragment, (key, value));
+    }
+
+    /// 全有或全无地应用一批更新：`Some(value)` 为写入，`None` 为删除
+    ///
+    /// 按全部写入都是新键一次性预留空间，并顺带清理墓碑；容量检查与分配都在
+    /// 修改任何元素之前完成，之后的写入和删除不会再分配内存。因此返回错误时
+    /// 映射的内容、容量与版本号都保持不变，成功时版本号加一。
+    ///
+    /// 只有向量策略提供这个方法：向量策略的键值对存放在预先分配的稠密数组中，
+    /// 而节点策略每次写入新键都要单独分配节点，无法在修改前预留全部内存。
+    pub fn apply_batch<I>(&mut self, batch: I) -> Result<BatchReport, MapError>
+    where
+        I: IntoIterator<Item = (K, Option<V>)>,
+    {
+        let batch: Vec<(K, Option<V>)> = batch.into_iter().collect();
+        let upserts = batch.iter().filter(|(_, value)| value.is_some()).count();
+
+        let required = self
+            .len()
+            .checked_add(upserts)
+            .and_then(capacity_for)
+            .ok_or(MapError::CapacityExceeded)?;
+        if required > self.capacity() || self.storage.deleted() > 0 {
+            let capacity = required.max(self.capacity());
+            self.rehash(capacity)?;
         }
-        
-        // 计算布局
-        let total_size = Self::calculate_layout(capacity)?;
-        
-        // 分配内存
-        let ptr = unsafe { AlignedAllocator::alloc_aligned(total_size) }?;
-        
-        // 初始化控制字节为EMPTY
-        dispatch_simd!(
-            fill_ctrls, 
-            ptr.as_ptr(),
-            EMPTY,
-            capacity
-        );
-        
-        // 设置键值对指针（控制字节之后）
-        let entries_ptr = unsafe { ptr.as_ptr().add(capacity) } as *mut KeyValuePair<K, V>;
-        let entries = NonNull::new(entries_ptr).ok_or(MapError::CapacityExceeded)?;
-        
-        Ok((ptr, entries))
-    }
-    
-    /// 释放内存
-    unsafe fn deallocate(&self) {
-        if self.capacity == 0 {
-            return;
+
+        let mut report = BatchReport::default();
+        for (key, value) in batch {
+            match value {
+                Some(value) => {
+                    // 新键写入预留好的空间，不会扩容或分配
+                    let hash = self.hash_of(&key);
+                    match self.get_mut_with_hash(hash, &key) {
+                        Some(slot) => {
+                            *slot = value;
+                            report.updated += 1;
+                        }
+                        None => {
+                            self.insert_unique_no_grow(hash, key, value);
+                            report.inserted += 1;
+                        }
+                    }
+                }
+                None => match self.remove(&key) {
+                    Some(_) => report.removed += 1,
+                    None => report.missing += 1,
+                },
+            }
         }
-        
-        let total_size = Self::calculate_layout(self.capacity)
-            .expect("Invalid layout calculation");
-        unsafe { AlignedAllocator::dealloc_aligned(self.ctrls.as_ptr(), total_size) };
+        self.batch_version += 1;
+        report.batch_version = self.batch_version;
+        Ok(report)
     }
-    
-    
-   
-
 }
-    
-   
 
-impl<K, V, S> F14VectorMap<K, V, S>
+impl<K, V, S, P> F14Map<K, V, S, P>
 where
     K: Sized,  // 添加必要的约束
-    V: Sized, 
+    V: Sized,
     S: BuildHasherExt + Default,
+    P: StoragePolicy,
 {
-    /// 创建一个新的 F14VectorMap
+    /// 创建一个新的 F14Map
     pub fn new() ->  Result<Self, MapError> {
         Self::with_capacity_and_hasher(0, S::default())
     }
-    
-    /// 创建具有指定容量的 F14VectorMap
+
+    /// 创建具有指定容量的 F14Map
     pub fn with_capacity(capacity: usize) ->  Result<Self, MapError> {
         Self::with_capacity_and_hasher(capacity, S::default())
     }
 }
 
-impl<K, V, S> F14VectorMap<K, V, S>
+impl<K, V, S, P> F14Map<K, V, S, P>
 where
     K: Sized,  // 添加必要的约束
-    V: Sized, 
-    
+    V: Sized,
     S: BuildHasherExt,
+    P: StoragePolicy,
 {
-    /// 使用指定的哈希构建器创建 F14VectorMap
+    /// 使用指定的哈希构建器创建 F14Map
     pub fn with_hasher(hasher: S) ->  Result<Self, MapError> {
         Self::with_capacity_and_hasher(0, hasher)
     }
-    
-    /// 使用指定容量和哈希构建器创建 F14VectorMap
+
+    /// 使用指定容量和哈希构建器创建 F14Map
     pub fn with_capacity_and_hasher(capacity: usize, hasher: S) ->  Result<Self, MapError> {
-        // 确保容量是CHUNK_SIZE的倍数
-        let group_count = if capacity > 0 {
-            (capacity + CHUNK_SIZE - 1) / CHUNK_SIZE
-        } else {
-            0
-        };
-        
-        let capacity = group_count * CHUNK_SIZE;
-        // 检查容量是否过大（在分配内存前）
-        if capacity > MAX_CAPACITY {
-            return Err(MapError::CapacityExceeded);
-        }
-        // 分配内存
-        let (ctrls, entries) = if capacity > 0 {
-            unsafe { Self::allocate(capacity).expect("Allocation failed") }
-        } else {
-            (
-                NonNull::dangling(),
-                NonNull::dangling(),
-            )
-        };
-        
-         Ok(F14VectorMap {
-            ctrls,
-            entries,
-            capacity,
-            group_count,
-            len: 0,
-            deleted: 0,
+        Self::with_capacity_and_hasher_in(capacity, hasher, AlignedAllocator)
+    }
+}
+
+impl<K, V, S, P, A> F14Map<K, V, S, P, A>
+where
+    K: Sized,  // 添加必要的约束
+    V: Sized,
+    S: BuildHasherExt + Default,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 创建使用指定分配器的 F14Map
+    pub fn new_in(alloc: A) ->  Result<Self, MapError> {
+        Self::with_capacity_and_hasher_in(0, S::default(), alloc)
+    }
+
+    /// 创建具有指定容量、使用指定分配器的 F14Map
+    pub fn with_capacity_in(capacity: usize, alloc: A) ->  Result<Self, MapError> {
+        Self::with_capacity_and_hasher_in(capacity, S::default(), alloc)
+    }
+}
+
+impl<K, V, S, P, A> F14Map<K, V, S, P, A>
+where
+    K: Sized,  // 添加必要的约束
+    V: Sized,
+
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 使用指定的哈希构建器和分配器创建 F14Map
+    pub fn with_hasher_in(hasher: S, alloc: A) ->  Result<Self, MapError> {
+        Self::with_capacity_and_hasher_in(0, hasher, alloc)
+    }
+
+    /// 使用指定容量、哈希构建器和分配器创建 F14Map
+    ///
+    /// 分组表与键值对的内存都通过 `alloc` 分配。
+    pub fn with_capacity_and_hasher_in(capacity: usize, hasher: S, alloc: A) ->  Result<Self, MapError> {
+        Ok(F14Map {
+            storage: P::Storage::with_capacity_in(capacity, alloc)?,
             hasher_builder: hasher,
-            phantom: PhantomData,
+            batch_version: 0,
         })
     }
-    
+
     /// 计算键的哈希和片段
     fn hash_key<Q>(&self, key: &Q) -> (u64, u8)
     where
         Q: Hash + ?Sized,
     {
-        let mut hasher = self.hasher_builder.build_hasher_ext();
-        key.hash(&mut hasher);
-        let (full_hash, fragment) = hasher.finish_ext();
-    
-    // 确保片段在有效范围内
-    let fragment = fragment & 0x7F; // 只保留低7位
-    //info!("Hashing key: {}, full_hash: {}, fragment: {}", std::any::type_name::<Q>(), full_hash, fragment);
-    (full_hash, fragment)
+        make_hash(&self.hasher_builder, key)
     }
-    
-    /// 获取组起始索引
+
+    /// 计算键的完整哈希，可配合 `*_with_hash` 系列方法复用
+    ///
+    /// 同一个哈希构建器（包括其克隆）对同一个键总是得到相同的结果，
+    /// 因此可以只计算一次，再在共享哈希构建器的多个映射中查找。
     #[inline]
-    fn group_start(&self, full_hash: u64) -> usize {
-        if self.group_count == 0 {
-            return 0; // 容量为0时返回0
+    pub fn hash_of<Q>(&self, key: &Q) -> u64
+    where
+        Q: Hash + ?Sized,
+    {
+        self.hash_key(key).0
+    }
+
+    /// 沿探测序列查找键所在的分组槽位
+    fn find_slot<Q>(&self, full_hash: u64, fragment: u8, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.storage.find(full_hash, fragment, |stored| stored.borrow() == key)
+    }
+
+    /// 为插入探测：找到键时返回 `Ok(槽位)`，否则返回 `Err(首个空闲槽位)`
+    ///
+    /// 先按溢出计数查找键，未找到再沿同一探测序列寻找空位；
+    /// 整条探测序列都没有空位时返回 `Err(None)`。
+    fn find_or_find_insert_slot<Q>(
+        &self,
+        full_hash: u64,
+        fragment: u8,
+        key: &Q,
+    ) -> Result<usize, Option<usize>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        match self.find_slot(full_hash, fragment, key) {
+            Some(slot) => Ok(slot),
+            None => Err(self.storage.find_insert_slot(full_hash, fragment)),
         }
-        
-        // 计算组索引 = 哈希值 % 分组数
-        let group_index = full_hash as usize % self.group_count;
-        
-        // 计算组起始索引 = 组索引 * 组大小
-        let start =group_index * CHUNK_SIZE;
-      //  info!("group_start: full_hash={}, group_count={}, group_index={}, start={}", full_hash, self.group_count, group_index, start);
-        start
-    }
-    
+    }
+
+    /// 按当前内容重建容量为 `capacity` 的分组表
+    fn rehash(&mut self, capacity: usize) -> Result<(), MapError>
+    where
+        K: Hash,
+    {
+        let hasher_builder = &self.hasher_builder;
+        self.storage.rehash(capacity, |key| make_hash(hasher_builder, key))
+    }
+
     /// 重建表以减少墓碑
+    ///
+    /// 在原有内存中原地重新放置元素，不会分配内存，因此不会失败；
+    /// 保留 `Result` 返回值以兼容已有调用。
    pub  fn rebuild(&mut self) -> Result<(), MapError>
     where
         K: Eq + Hash,
-     S: Clone,
     {
          // 如果没有墓碑，直接返回
-    if self.deleted == 0 {
+    if self.storage.deleted() == 0 {
         return Ok(());
     }
-    info!("开始重建表: len={}, deleted={}", self.len, self.deleted);
-        // 创建新表，容量相同但删除墓碑
-        let mut new_table = F14VectorMap::with_capacity_and_hasher(
-            self.capacity(),
-            self.hasher_builder.clone(),
-        )?;
-        
-        // 迁移所有元素
-    let mut migrated = 0;
-    for index in 0..self.capacity {
-        let ctrl = self.get_ctrl(index);
-        
-        // 只处理FULL状态的槽位（高位为0）
-        if ctrl < 128 {
-            unsafe {
-                // 获取键值对
-                let entry_ptr = self.entries_ptr().add(index);
-                let key_ptr = &(*entry_ptr).key as *const _ as *const K;
-                let value_ptr = &(*entry_ptr).value as *const _ as *const V;
-                
-                let key = ptr::read(key_ptr);
-                let value = ptr::read(value_ptr);
-                
-                // 插入新表
-                new_table.insert(key, value)?;
-                
-                // 标记原槽位为EMPTY
-                self.set_ctrl(index, EMPTY);
-                
-                migrated += 1;
-            }
-        }
-    }
-    
-    info!("迁移完成: 迁移了 {} 个元素", migrated);
-    
-    // 交换表
-    *self = new_table;
-    info!("重建完成: 新 len={}, deleted={}", self.len, self.deleted);
+    info!("开始重建表: len={}, deleted={}", self.len(), self.storage.deleted());
+        // 容量相同，只重新放置元素
+        self.rehash_in_place();
+    info!("重建完成: 新 len={}, deleted={}", self.len(), self.storage.deleted());
     Ok(())
     }
-    
+
+    /// 原地整理分组表：清除墓碑，并把远离起始组的元素移回探测序列中更靠前的分组
+    ///
+    /// 大量删除后，早先因分组已满而溢出的元素仍留在后续分组，查找需要多探测几组；
+    /// 整理后探测长度回到与重新插入相同的水平。不分配内存，适合在空闲时段手动触发。
+    pub fn compact(&mut self)
+    where
+        K: Hash,
+    {
+        self.rehash_in_place();
+    }
+
+    /// 在原有内存中重新放置所有元素
+    fn rehash_in_place(&mut self)
+    where
+        K: Hash,
+    {
+        let hasher_builder = &self.hasher_builder;
+        self.storage.rehash_in_place(|key| make_hash(hasher_builder, key));
+    }
+
     /// 扩容表
     fn resize(&mut self) -> Result<(), MapError>
     where
         K: Eq + Hash,
-        S: Clone,
     {
-        info!("开始扩容: 当前 len={}, capacity={}", self.len, self.capacity);
+        info!("开始扩容: 当前 len={}, capacity={}", self.len(), self.capacity());
         // 计算新容量（翻倍或初始大小）
-        let new_capacity = if self.capacity == 0 {
+        let new_capacity = if self.capacity() == 0 {
             CHUNK_SIZE
         } else {
-            self.capacity * 2
+            self.capacity() * 2
         };
         info!("新容量: {}", new_capacity);
-        // 创建新表
-        let mut new_table = F14VectorMap::with_capacity_and_hasher(
-            new_capacity,
-            self.hasher_builder.clone(),
-        )?;
-        
-         let mut migrated = 0;
-        // 迁移所有元素
-        for index in 0..self.capacity {
-            if self.get_ctrl(index) < 128 { // 高位为0表示FULL
-            unsafe {
-                let (key, value) = self.replace_slot_state(index, SlotState::Empty);
-                new_table.insert(key, value)?;
-                migrated += 1;
-            }
+        self.rehash(new_capacity)?;
+        info!("扩容完成: 新 len={}, capacity={}", self.len(), self.capacity());
+        Ok(())
+    }
+
+    /// 预留至少 `additional` 个元素的空间，之后的插入不会触发扩容
+    ///
+    /// # Panics
+    /// 容量溢出或分配失败时 panic，需要处理错误时使用 [`F14Map::try_reserve`]。
+    pub fn reserve(&mut self, additional: usize)
+    where
+        K: Eq + Hash,
+    {
+        if let Err(err) = self.try_reserve(additional) {
+            panic!("F14Map::reserve failed: {}", err);
+        }
+    }
+
+    /// 预留至少 `additional` 个元素的空间，失败时返回错误且映射保持不变
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), MapError>
+    where
+        K: Eq + Hash,
+    {
+        let required = self
+            .len()
+            .checked_add(additional)
+            .and_then(capacity_for)
+            .ok_or(MapError::CapacityExceeded)?;
+        if required <= self.capacity() {
+            return Ok(());
+        }
+        self.rehash(required)
+    }
+
+    /// 尽可能缩小容量，只保留当前元素所需的空间
+    pub fn shrink_to_fit(&mut self)
+    where
+        K: Eq + Hash,
+    {
+        self.shrink_to(0);
+    }
+
+    /// 缩小容量，但至少保留 `min_capacity` 个元素的空间
+    ///
+    /// 当前容量已不大于目标容量时不做任何事；重新分配失败时保持原样。
+    pub fn shrink_to(&mut self, min_capacity: usize)
+    where
+        K: Eq + Hash,
+    {
+        let Some(target) = capacity_for(self.len().max(min_capacity)) else {
+            return;
+        };
+        if target >= self.capacity() {
+            return;
         }
+        // 缩容只是优化，分配失败时保留原表
+        let _ = self.rehash(target);
+    }
+
+    /// 插入迭代器中的所有键值对，已存在的键被覆盖
+    ///
+    /// 按 `size_hint` 预留空间（映射非空时只预留一半，重复键通常不占新位置）。
+    /// 出错时已插入的键值对保留在映射中。
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), MapError>
+    where
+        K: Eq + Hash,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let iter = iter.into_iter();
+        let hint = iter.size_hint().0;
+        self.try_reserve(if self.is_empty() { hint } else { hint.div_ceil(2) })?;
+        for (key, value) in iter {
+            self.insert(key, value)?;
         }
-        info!("迁移完成: 迁移了 {} 个元素", migrated);
-        // 交换表
-        *self = new_table;
-        info!("扩容完成: 新 len={}, capacity={}", self.len, self.capacity);
         Ok(())
     }
-    
+
     /// 插入键值对
     pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, MapError>
     where
         K: Eq + Hash,
-        S: Clone,
     {
-        // 如果容量为0，直接扩容
-        if self.capacity == 0 {
-            self.resize()?;
+        let hash = self.hash_of(&key);
+        self.insert_with_hash(hash, key, value)
+    }
+
+    /// 使用预先计算的哈希插入键值对
+    ///
+    /// `hash` 必须等于 [`F14Map::hash_of`] 对 `key` 的结果，否则查找会失败、
+    /// 计数会被破坏（属于逻辑错误，不会导致内存不安全）。
+    pub fn insert_with_hash(&mut self, hash: u64, key: K, value: V) -> Result<Option<V>, MapError>
+    where
+        K: Eq + Hash,
+    {
+        match self.try_entry_with_hash(hash, key)? {
+            Entry::Occupied(mut entry) => Ok(Some(entry.insert(value))),
+            Entry::Vacant(entry) => {
+                entry.try_insert_entry(value)?;
+                Ok(None)
+            }
         }
-        
+    }
+
+    /// 获取键对应的条目，用于原地更新或插入
+    ///
+    /// # Panics
+    /// 扩容或重建失败时 panic，需要处理错误时使用 [`F14Map::try_entry`]。
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S, P, A>
+    where
+        K: Eq + Hash,
+    {
+        match self.try_entry(key) {
+            Ok(entry) => entry,
+            Err(err) => panic!("F14Map::entry failed: {}", err),
+        }
+    }
+
+    /// 获取键对应的条目，扩容或重建失败时返回错误
+    ///
+    /// 只计算一次哈希：返回的 [`VacantEntry`] 已经定位好插入槽位，
+    /// 并且此处已完成扩容/重建检查，之后的插入不会再分配内存。
+    pub fn try_entry(&mut self, key: K) -> Result<Entry<'_, K, V, S, P, A>, MapError>
+    where
+        K: Eq + Hash,
+    {
+        let hash = self.hash_of(&key);
+        self.try_entry_with_hash(hash, key)
+    }
+
+    /// 使用预先计算的哈希获取条目（`hash` 的要求见 [`F14Map::insert_with_hash`]）
+    ///
+    /// # Panics
+    /// 扩容或重建失败时 panic，需要处理错误时使用 [`F14Map::try_entry_with_hash`]。
+    pub fn entry_with_hash(&mut self, hash: u64, key: K) -> Entry<'_, K, V, S, P, A>
+    where
+        K: Eq + Hash,
+    {
+        match self.try_entry_with_hash(hash, key) {
+            Ok(entry) => entry,
+            Err(err) => panic!("F14Map::entry_with_hash failed: {}", err),
+        }
+    }
+
+    /// 使用预先计算的哈希获取条目，扩容或重建失败时返回错误
+    pub fn try_entry_with_hash(&mut self, hash: u64, key: K) -> Result<Entry<'_, K, V, S, P, A>, MapError>
+    where
+        K: Eq + Hash,
+    {
+        debug_assert_eq!(hash, self.hash_of(&key), "hash does not match key");
+        self.prepare_insert()?;
+        let (full_hash, fragment) = split_hash(hash);
+
+        loop {
+            match self.find_or_find_insert_slot(full_hash, fragment, &key) {
+                Ok(slot) => {
+                    return Ok(Entry::Occupied(OccupiedEntry::new(self, Some(key), full_hash, slot, fragment)));
+                }
+                Err(Some(slot)) => {
+                    return Ok(Entry::Vacant(VacantEntry::new(self, key, full_hash, slot, fragment)));
+                }
+                // 探测失败，扩容后重试（哈希无需重新计算）
+                Err(None) => self.resize()?,
+            }
+        }
+    }
+
+    /// 插入新键前的重建与扩容检查
+    fn prepare_insert(&mut self) -> Result<(), MapError>
+    where
+        K: Eq + Hash,
+    {
         // 重建检查
-        if self.deleted > self.len / 2 {
+        if self.storage.deleted() > self.len() / 2 {
             self.rebuild()?;
         }
-        
-        // 扩容检查
-        if self.len >= self.capacity * 7 / 10 {
+
+        // 扩容检查（容量为0时同样需要扩容）
+        if self.storage.needs_grow() {
             self.resize()?;
         }
-        
-        let (full_hash, fragment) = self.hash_key(&key);
-      //  info!("insert 键 {} 的哈希: full_hash={}, fragment={}", std::any::type_name::<K>(), full_hash, fragment);
-        let group_start = self.group_start(full_hash);
-      //  info!("insert 分组数: {}, group_start={}", self.group_count, group_start);
-        let fragment = simd_utils::make_ctrl_byte(fragment);
-        
-                
-        // 2. 检查键是否已存在
-        if let Some(index) = self.find_in_group(group_start, &key, fragment) {
-            return self.replace_value(index, value);
-        }
-        // 1. 在初始组内查找空闲位置
-        if let Some(index) = self.find_empty_in_group(group_start) {
-            return self.insert_at(index, key, value, fragment);
-        }
-        
-        // 3. 二次哈希探测其他组
-        let step = (full_hash as usize % self.group_count) | 1;
-        let mut group_index = (group_start / CHUNK_SIZE + step) % self.group_count;
-        let mut probe_count = 0;
-        
-        while probe_count < self.group_count * 2 {
-            let group_start = group_index * CHUNK_SIZE;
-            
-            // 在组内查找空闲位置
-            if let Some(index) = self.find_empty_in_group(group_start) {
-                return self.insert_at(index, key, value, fragment);
-            }
-            
-            // 检查键是否已存在
-            if let Some(index) = self.find_in_group(group_start, &key, fragment) {
-                return self.replace_value(index, value);
+        Ok(())
+    }
+
+    /// 插入调用方保证不存在的键，返回所在槽位（内部使用）
+    pub(crate) fn insert_unique_hashed(&mut self, full_hash: u64, fragment: u8, key: K, value: V) -> Result<usize, MapError>
+    where
+        K: Eq + Hash,
+    {
+        self.prepare_insert()?;
+        loop {
+            match self.storage.find_insert_slot(full_hash, fragment) {
+                Some(slot) => {
+                    self.insert_at(slot, key, value, full_hash, fragment)?;
+                    return Ok(slot);
+                }
+                // 探测失败，扩容后重试
+                None => self.resize()?,
             }
-            
-            // 跳到下一个组
-            group_index = (group_index + step) % self.group_count;
-            probe_count += 1;
         }
-        
-        // 4. 如果探测失败，扩容后重试
-        self.resize()?;
-        self.insert(key, value)
     }
-    
-    /// 在组内查找空闲位置
-    #[inline]
-    fn find_empty_in_group(&self, group_start: usize) -> Option<usize> {
-        dispatch_simd!(
-            find_empty,
-            unsafe {
-               self.ctrls_ptr().add(group_start) 
-            }
-            
-        ).map(|slot| group_start + slot)
+
+    /// 在空闲槽位插入键值对，失败时映射保持不变 (内部使用)
+    pub(crate) fn insert_at(&mut self, slot: usize, key: K, value: V, full_hash: u64, fragment: u8) -> Result<(), MapError> {
+        self.storage.insert_at(slot, full_hash, fragment, (key, value))?;
+        Ok(())
     }
-    
-    /// 在组内查找键
-    #[inline]
-    fn find_in_group<Q>(
-        &self,
-        group_start: usize,
-        key: &Q,
-        fragment: u8,
-    ) -> Option<usize>
+
+    /// 查找键
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
     where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-       
-        // 确保索引在有效范围内
-    if group_start >= self.capacity {
-        return None;
-    }
-    // 使用 SIMD 查找匹配片段的位置
-    // 使用 SIMD 查找所有匹配片段的位置
-    let matches = unsafe {
-        simd_utils::find_all_matches(
-            self.ctrls_ptr().add(group_start),
-            fragment
-        )
-    };
-    // 检查所有匹配位置
-    for &slot_in_group in matches.iter() {
-        // 遇到 0xFF 表示结束
-        if slot_in_group == 0xFF {
-            break;
-        }
-        
-        let index = group_start + slot_in_group as usize;
-        
-        // 确保索引在有效范围内
-        if index >= self.capacity {
-            continue;
-        }
-        
-        // 验证键是否匹配
-        unsafe {
-            let entry_ptr = self.entries_ptr().add(index);
-            let key_ptr = (*entry_ptr).key.as_ptr() as *const K;
-            let candidate_key = &*key_ptr;
-            
-            if candidate_key.borrow().eq(key) {
-                return Some(index);
-            }
-        }
+        self.get_key_value(key).map(|(_, value)| value)
     }
-    
-    None
-}
-    /// 在指定位置插入键值对
-    // 修改insert_at函数
-fn insert_at(&mut self, index: usize, key: K, value: V, fragment: u8) -> Result<Option<V>, MapError> {
-    let state = self.slot_state(index);
-    
-    // 写入数据
-    unsafe {
-        let entry_ptr = self.entries_ptr().add(index);
-        ptr::write(&mut (*entry_ptr).key, MaybeUninit::new(key));
-        ptr::write(&mut (*entry_ptr).value, MaybeUninit::new(value));
-    }
-    
-    // 更新控制字节
-    self.set_ctrl(index, fragment);
-    
-    // 更新计数
-    match state {
-        SlotState::Full => panic!("Inserting into a full slot"),
-        SlotState::Deleted => self.deleted -= 1,
-        SlotState::Empty => {},
-    }
-    
-    self.len += 1;
-    info!("插入成功: index={}, len={}", index, self.len);
-    Ok(None)
-}
 
-// 修改replace_value函数
-fn replace_value(&mut self, index: usize, value: V) -> Result<Option<V>,  MapError> {
-    let state = self.slot_state(index);
-    if state != SlotState::Full {
-        return Err(MapError::InvalidSlotState);
-    }
-    
-    // 替换值并返回旧值
-    unsafe {
-        let entry_ptr = self.entries_ptr().add(index);
-         // 保存旧值
-        let old_value = ptr::read(&(*entry_ptr).value).assume_init();
-        
-        // 写入新值
-        ptr::write(&mut (*entry_ptr).value, MaybeUninit::new(value));
-        
-        Ok(Some(old_value))
+    /// 查找键，返回值的可变引用
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value_mut(key).map(|(_, value)| value)
     }
-}
 
+    /// 查找键，返回映射中存储的键和值
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slot = self.lookup(key)?;
+        let (key, value) = self.pair_at(slot);
+        Some((key, value))
+    }
 
-    
-    /// 查找键
-    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    /// 查找键，返回存储的键和值的可变引用
+    ///
+    /// 键只能以不可变引用返回，修改键会破坏其在表中的位置。
+    pub fn get_key_value_mut<Q>(&mut self, key: &Q) -> Option<(&K, &mut V)>
     where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-        let (full_hash, fragment) = self.hash_key(key);
-      //  info!("get 键 {} 的哈希: full_hash={}, fragment={}", std::any::type_name::<Q>(), full_hash, fragment);
-        
-        let group_start = self.group_start(full_hash);
-      //  info!("get 分组数: {}, group_start={}", self.group_count, group_start);
-
-        let fragment = simd_utils::make_ctrl_byte(fragment);
-        // 1. 在初始组内查找
-        if let Some(index) = self.find_in_group(group_start, key, fragment) {
-            unsafe {
-                let entry_ptr = self.entries_ptr().add(index);
-                let value_ptr = (*entry_ptr).value.as_ptr() as *const V;
-                return Some(&*value_ptr);
+        let slot = self.lookup(key)?;
+        let (key, value) = self.pair_at_mut(slot);
+        Some((&*key, value))
+    }
+
+    /// 检查键是否存在
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.lookup(key).is_some()
+    }
+
+    /// 批量查找，结果与 `keys` 一一对应
+    ///
+    /// 见 [`get_batch_into`](Self::get_batch_into)。
+    pub fn get_batch<Q>(&self, keys: &[Q]) -> Vec<Option<&V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        let mut out = vec![None; keys.len()];
+        self.get_batch_into(keys, &mut out);
+        out
+    }
+
+    /// 批量查找，把结果写入 `out`
+    ///
+    /// 每 [`BATCH_PREFETCH`] 个键为一批：先计算整批的哈希并预取各自起始分组的控制字节和槽位，
+    /// 再逐个完成查找，多个键的访存延迟得以重叠。
+    ///
+    /// # Panics
+    /// `keys` 与 `out` 长度不同时 panic。
+    pub fn get_batch_into<'a, Q>(&'a self, keys: &[Q], out: &mut [Option<&'a V>])
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        assert_eq!(keys.len(), out.len(), "get_batch_into: output length must match keys");
+        let mut hashes = [(0u64, 0u8); BATCH_PREFETCH];
+        for (keys, out) in keys.chunks(BATCH_PREFETCH).zip(out.chunks_mut(BATCH_PREFETCH)) {
+            for (key, hash) in keys.iter().zip(hashes.iter_mut()) {
+                *hash = self.hash_key(key);
+                self.storage.prefetch(hash.0, hash.1);
             }
-        }
-        
-        // 2. 二次哈希探测其他组
-        let step = (full_hash as usize % self.group_count) | 1;
-        let mut group_index = (group_start / CHUNK_SIZE + step) % self.group_count;
-        let mut probe_count = 0;
-        
-        while probe_count < self.group_count * 2 {
-            let group_start = group_index * CHUNK_SIZE;
-            
-            // 在组内查找键
-            if let Some(index) = self.find_in_group(group_start, key, fragment) {
-                unsafe {
-                    let entry_ptr = self.entries_ptr().add(index);
-                let value_ptr = (*entry_ptr).value.as_ptr() as *const V;
-                return Some(&*value_ptr);
-                }
+            for ((key, &(full_hash, fragment)), result) in keys.iter().zip(&hashes).zip(out) {
+                *result = self.find_slot(full_hash, fragment, key).map(|slot| &self.pair_at(slot).1);
             }
-            
-            // 跳到下一个组
-            group_index = (group_index + step) % self.group_count;
-            probe_count += 1;
         }
-        
-        None
     }
-    
-    /// 移除键
+
+    /// 移除键，返回值
     pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q> + Hash,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// 移除键，返回映射中存储的键和值
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q> + Hash,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (full_hash, fragment) = self.hash_key(key);
+        let slot = self.find_slot(full_hash, fragment, key)?;
+        Some(self.take_at(slot, full_hash, fragment))
+    }
+
+    /// 使用预先计算的哈希查找键（`hash` 的要求见 [`F14Map::insert_with_hash`]）
+    pub fn get_with_hash<Q>(&self, hash: u64, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let (full_hash, fragment) = split_hash(hash);
+        let slot = self.find_slot(full_hash, fragment, key)?;
+        Some(&self.pair_at(slot).1)
+    }
+
+    /// 使用预先计算的哈希查找键，返回值的可变引用
+    pub fn get_mut_with_hash<Q>(&mut self, hash: u64, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let (full_hash, fragment) = split_hash(hash);
+        let slot = self.find_slot(full_hash, fragment, key)?;
+        Some(&mut self.pair_at_mut(slot).1)
+    }
+
+    /// 使用预先计算的哈希移除键，返回值
+    pub fn remove_with_hash<Q>(&mut self, hash: u64, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q> + Hash,
+        Q: Eq + ?Sized,
+    {
+        let (full_hash, fragment) = split_hash(hash);
+        let slot = self.find_slot(full_hash, fragment, key)?;
+        Some(self.take_at(slot, full_hash, fragment).1)
+    }
+
+    /// 以哈希和相等判断闭包查找，无需构造键
+    pub fn raw_entry(&self) -> RawEntryBuilder<'_, K, V, S, P, A> {
+        RawEntryBuilder::new(self)
+    }
+
+    /// 以哈希和相等判断闭包获取可修改的条目，无需构造键
+    pub fn raw_entry_mut(&mut self) -> RawEntryBuilderMut<'_, K, V, S, P, A> {
+        RawEntryBuilderMut::new(self)
+    }
+
+    /// 沿探测序列查找满足 `is_match` 的分组槽位（内部使用）
+    #[inline]
+    pub(crate) fn find_hashed(&self, full_hash: u64, fragment: u8, is_match: impl FnMut(&K) -> bool) -> Option<usize> {
+        self.storage.find(full_hash, fragment, is_match)
+    }
+
+    /// 计算哈希并沿探测序列查找键所在的分组槽位
+    #[inline]
+    fn lookup<Q>(&self, key: &Q) -> Option<usize>
     where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
         let (full_hash, fragment) = self.hash_key(key);
-        let fragment = simd_utils::make_ctrl_byte(fragment);
-        let group_start = self.group_start(full_hash);
-        
-        // 1. 在初始组内查找
-        if let Some(index) = self.find_in_group(group_start, key, fragment) {
-            return self.remove_at(index);
-        }
-        
-        // 2. 二次哈希探测其他组
-        let step = (full_hash as usize % self.group_count) | 1;
-        let mut group_index = (group_start / CHUNK_SIZE + step) % self.group_count;
-        let mut probe_count = 0;
-        
-        while probe_count < self.group_count * 2 {
-            let group_start = group_index * CHUNK_SIZE;
-            
-            // 在组内查找键
-            if let Some(index) = self.find_in_group(group_start, key, fragment) {
-                return self.remove_at(index);
-            }
-            
-            // 跳到下一个组
-            group_index = (group_index + step) % self.group_count;
-            probe_count += 1;
-        }
-        
-        None
-    }
-    
-    // 修改remove_at函数
-fn remove_at(&mut self, index: usize) -> Option<V> {
-    // 确保槽位状态为 FULL
-        if self.get_ctrl(index) >= 128 {
-            return None;
-        }
-        // 标记为删除
-        self.set_ctrl(index, DELETED);
-        self.deleted += 1;
-        self.len -= 1;
-        
-        // 取出值
-         // 取出值
-        unsafe {
-            let value_ptr = &(*self.entries_ptr().add(index)).value as *const _ as *const V;
-            let value = ptr::read(value_ptr);
-            Some(value)
-        }
+        self.find_slot(full_hash, fragment, key)
     }
-    
-    
-    
-    /// 获取迭代器
-    pub fn iter(&self) -> Iter<'_, K, V, S> {
-        Iter::new(self)
+
+    /// 只保留 `keep` 返回 `true` 的键值对
+    ///
+    /// 按分组扫描控制字节，删除的槽位直接恢复为 EMPTY，不留墓碑。
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        K: Hash,
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.extract_if(|key, value| !keep(key, value)).for_each(drop);
     }
-    
-    /// 获取可变迭代器
-    pub fn iter_mut(&mut self) -> IterMut<'_, K, V, S> {
-        IterMut::new(self)
+
+    /// 惰性移出 `pred` 返回 `true` 的键值对
+    ///
+    /// 迭代器未耗尽就被析构时，尚未检查的键值对保留在映射中。
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, K, V, F, S, P, A>
+    where
+        K: Hash,
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        ExtractIf::new(self, pred)
     }
 
-    /// 获取消耗迭代器
-    pub fn into_iter(self) -> IntoIter<K, V, S> {
-        IntoIter::new(self)
+    /// 重新计算哈希并移出分组槽位对应的键值对（内部使用）
+    pub(crate) fn take_slot(&mut self, slot: usize) -> (K, V)
+    where
+        K: Hash,
+    {
+        let (full_hash, fragment) = self.hash_key(&self.pair_at(slot).0);
+        self.take_at(slot, full_hash, fragment)
     }
-}
 
+    /// 移出分组槽位对应的键值对（内部使用）
+    ///
+    /// 槽位直接恢复为 EMPTY 并撤销溢出计数，不留墓碑。
+    pub(crate) fn take_at(&mut self, slot: usize, full_hash: u64, fragment: u8) -> (K, V)
+    where
+        K: Hash,
+    {
+        let hasher_builder = &self.hasher_builder;
+        self.storage
+            .erase_at(slot, full_hash, fragment, |key| make_hash(hasher_builder, key))
+    }
 
-impl<K, V, S> F14VectorMap<K, V, S>
-where
-    K: Sized,
-    V: Sized,
-{
-    /// 内部清理方法
-    fn internal_clear(&mut self) {
-        for index in 0..self.capacity {
-            match self.slot_state(index) {
-                SlotState::Full => {
-                    unsafe {
-                        let key_ptr = &mut (*self.entries_ptr().add(index)).key as *mut _ as *mut K;
-                        let val_ptr = &mut (*self.entries_ptr().add(index)).value as *mut _ as *mut V;
-                        
-                        ptr::drop_in_place(key_ptr);
-                        ptr::drop_in_place(val_ptr);
-                    }
-                    self.set_ctrl(index, EMPTY);
-                }
-                SlotState::Deleted => {
-                    self.set_ctrl(index, EMPTY);
-                }
-                SlotState::Empty => {}
+    /// 替换槽位状态并返回旧数据（内部使用）
+    ///
+    /// 写入 `Deleted` 时保留溢出计数、留下墓碑（由 [`F14Map::rebuild`] 清理）；
+    /// 写入 `Empty` 时与普通删除相同。
+    ///
+    /// # Safety
+    /// `index` 处的槽位必须处于 FULL 状态，`new_state` 不能是 `Full`。
+    pub unsafe fn replace_slot_state(&mut self, index: usize, new_state: SlotState) -> (K, V)
+    where
+        K: Hash,
+    {
+        match new_state {
+            SlotState::Deleted => {
+                let hasher_builder = &self.hasher_builder;
+                self.storage.tombstone_at(index, |key| make_hash(hasher_builder, key))
+            }
+            SlotState::Empty => {
+                let (full_hash, fragment) = self.hash_key(&self.pair_at(index).0);
+                self.take_at(index, full_hash, fragment)
             }
+            SlotState::Full => panic!("replace_slot_state cannot mark a slot Full"),
         }
-        
-        self.len = 0;
-        self.deleted = 0;
-    }
-    
-    /// 公共 clear 方法
-    pub fn clear(&mut self) {
-        self.internal_clear();
     }
 }
 
-impl<K, V, S> Drop for F14VectorMap<K, V, S>
+impl<K, V, S, P, A> IntoIterator for F14Map<K, V, S, P, A>
 where
     K: Sized,  // 添加必要的约束
-    V: Sized,   // 确保类型可安全操作
- 
+    V: Sized,
+    P: StoragePolicy,
+    A: Allocator + Clone,
 {
-    fn drop(&mut self) {
-        // 释放所有元素
-        self.clear();
-        
-        // 释放内存
-        unsafe {
-            self.deallocate();
-        }
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, P, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.storage.into_iter())
     }
 }
 
-impl<K, V, S> IntoIterator for F14VectorMap<K, V, S> 
-where
-    K: Sized,  // 添加必要的约束
-    V: Sized, 
-{
-    type Item = (K, V);
-    type IntoIter = IntoIter<K, V, S>;
-    
+impl<'a, K, V, S, P: StoragePolicy, A: Allocator + Clone> IntoIterator for &'a F14Map<K, V, S, P, A> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, P, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S, P: StoragePolicy, A: Allocator + Clone> IntoIterator for &'a mut F14Map<K, V, S, P, A> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V, P, A>;
+
     fn into_iter(self) -> Self::IntoIter {
-        IntoIter::new(self)
+        self.iter_mut()
     }
 }
 
-impl<K: std::fmt::Debug, V: std::fmt::Debug, S> std::fmt::Debug for F14VectorMap<K, V, S> 
+impl<K: std::fmt::Debug, V: std::fmt::Debug, S, P, A> std::fmt::Debug for F14Map<K, V, S, P, A>
 where
     K: std::fmt::Debug,
     V: std::fmt::Debug,
-S: BuildHasherExt,  // 添加 BuildHasherExt 约束
+    P: StoragePolicy,
+    A: Allocator + Clone,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_map()
-            .entries(self.iter())  // 现在可以调用 iter()
+            .entries(self.iter())
             .finish()
     }
 }
 
-impl<K, V, S> Default for F14VectorMap<K, V, S>
+impl<K, V, S, P, A> Default for F14Map<K, V, S, P, A>
 where
     K: Sized,  // 添加必要的约束
-    V: Sized, 
-   
+    V: Sized,
+
     S: BuildHasherExt + Default,
+    P: StoragePolicy,
+    A: Allocator + Clone + Default,
 {
     fn default() ->  Self {
-        F14VectorMap {
-            ctrls: NonNull::dangling(),
-            entries: NonNull::dangling(),
-            capacity: 0,
-            group_count: 0,
-            len: 0,
-            deleted: 0,
+        F14Map {
+            storage: P::Storage::new_in(A::default()),
             hasher_builder: S::default(),
-            phantom: PhantomData,
+            batch_version: 0,
+        }
+    }
+}
+
+impl<K, V, S, P, A> Clone for F14Map<K, V, S, P, A>
+where
+    K: Clone,
+    V: Clone,
+    S: Clone,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// # Panics
+    /// 分配失败时 panic，需要处理错误时使用 [`F14Map::try_clone`]。
+    fn clone(&self) -> Self {
+        match self.try_clone() {
+            Ok(map) => map,
+            Err(err) => panic!("F14Map::clone failed: {}", err),
+        }
+    }
+}
+
+impl<K, V, S, P, A> PartialEq for F14Map<K, V, S, P, A>
+where
+    K: Eq + Hash,
+    V: PartialEq,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 键集合相同且每个键对应的值相等，与存储顺序无关
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self.iter().all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K, V, S, P, A> Eq for F14Map<K, V, S, P, A>
+where
+    K: Eq + Hash,
+    V: Eq,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+}
+
+impl<K, Q, V, S, P, A> std::ops::Index<&Q> for F14Map<K, V, S, P, A>
+where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    type Output = V;
+
+    /// # Panics
+    /// 键不存在时 panic。
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("F14Map::index: key not found")
+    }
+}
+
+impl<K, V, S, P, A> Extend<(K, V)> for F14Map<K, V, S, P, A>
+where
+    K: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// # Panics
+    /// 容量溢出或分配失败时 panic，需要处理错误时使用 [`F14Map::try_extend`]。
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        if let Err(err) = self.try_extend(iter) {
+            panic!("F14Map::extend failed: {}", err);
         }
     }
-}
\ No newline at end of file
+}
+
+impl<'a, K, V, S, P, A> Extend<(&'a K, &'a V)> for F14Map<K, V, S, P, A>
+where
+    K: Eq + Hash + Copy,
+    V: Copy,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
+    }
+}
+
+impl<K, V, S, P, A> FromIterator<(K, V)> for F14Map<K, V, S, P, A>
+where
+    K: Eq + Hash,
+    S: BuildHasherExt + Default,
+    P: StoragePolicy,
+    A: Allocator + Clone + Default,
+{
+    /// # Panics
+    /// 容量溢出或分配失败时 panic。
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, S, P, A, const N: usize> From<[(K, V); N]> for F14Map<K, V, S, P, A>
+where
+    K: Eq + Hash,
+    S: BuildHasherExt + Default,
+    P: StoragePolicy,
+    A: Allocator + Clone + Default,
+{
+    fn from(pairs: [(K, V); N]) -> Self {
+        Self::from_iter(pairs)
+    }
+}
@@ -0,0 +1,572 @@
+//! F14Set 实现
+//!
+//! 集合就是值类型为 `()` 的 [`F14Map`]，复用其分组表、存储策略与条目逻辑；
+//! 集合运算（并、交、差、对称差）以惰性迭代器的形式提供。
+
+use crate::{
+    allocator::{AlignedAllocator, Allocator},
+    entry::Entry,
+    error::MapError,
+    f14_map::F14Map,
+    iterators,
+    policy::{FastPolicy, NodePolicy, StoragePolicy, ValuePolicy, VectorPolicy},
+    traits::BuildHasherExt,
+};
+use std::{
+    borrow::Borrow, collections::hash_map::RandomState, hash::Hash, iter::{Chain, FusedIterator}
+};
+
+/// F14 哈希集合主结构，`P` 为存储策略，`A` 为内存分配器
+pub struct F14Set<T, S = RandomState, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    map: F14Map<T, (), S, P, A>,
+}
+
+/// 向量策略集合：元素连续存放
+pub type F14VectorSet<T, S = RandomState, A = AlignedAllocator> = F14Set<T, S, VectorPolicy, A>;
+
+/// 值策略集合：元素直接存放在分组表中
+pub type F14ValueSet<T, S = RandomState, A = AlignedAllocator> = F14Set<T, S, ValuePolicy, A>;
+
+/// 节点策略集合：元素单独分配
+pub type F14NodeSet<T, S = RandomState, A = AlignedAllocator> = F14Set<T, S, NodePolicy, A>;
+
+/// 快速集合：按元素大小选择值策略或向量策略
+pub type F14FastSet<T, S = RandomState, A = AlignedAllocator> = F14Set<T, S, FastPolicy, A>;
+
+impl<T, S, P: StoragePolicy, A: Allocator + Clone> F14Set<T, S, P, A> {
+    /// 获取容量
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// 获取元素数量
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// 检查是否为空
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// 获取迭代器
+    pub fn iter(&self) -> Iter<'_, T, P, A> {
+        Iter { inner: self.map.iter() }
+    }
+
+    /// 清空集合
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// 获取集合使用的分配器
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.map.allocator()
+    }
+}
+
+impl<T, S, P> F14Set<T, S, P>
+where
+    S: BuildHasherExt + Default,
+    P: StoragePolicy,
+{
+    /// 创建一个新的 F14Set
+    pub fn new() -> Result<Self, MapError> {
+        Ok(Self { map: F14Map::new()? })
+    }
+
+    /// 创建具有指定容量的 F14Set
+    pub fn with_capacity(capacity: usize) -> Result<Self, MapError> {
+        Ok(Self { map: F14Map::with_capacity(capacity)? })
+    }
+}
+
+impl<T, S, P> F14Set<T, S, P>
+where
+    S: BuildHasherExt,
+    P: StoragePolicy,
+{
+    /// 使用指定的哈希构建器创建 F14Set
+    pub fn with_hasher(hasher: S) -> Result<Self, MapError> {
+        Ok(Self { map: F14Map::with_hasher(hasher)? })
+    }
+
+    /// 使用指定容量和哈希构建器创建 F14Set
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Result<Self, MapError> {
+        Ok(Self { map: F14Map::with_capacity_and_hasher(capacity, hasher)? })
+    }
+}
+
+impl<T, S, P, A> F14Set<T, S, P, A>
+where
+    S: BuildHasherExt + Default,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 创建使用指定分配器的 F14Set
+    pub fn new_in(alloc: A) -> Result<Self, MapError> {
+        Ok(Self { map: F14Map::new_in(alloc)? })
+    }
+
+    /// 创建具有指定容量、使用指定分配器的 F14Set
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Result<Self, MapError> {
+        Ok(Self { map: F14Map::with_capacity_in(capacity, alloc)? })
+    }
+}
+
+impl<T, S, P, A> F14Set<T, S, P, A>
+where
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 使用指定的哈希构建器和分配器创建 F14Set
+    pub fn with_hasher_in(hasher: S, alloc: A) -> Result<Self, MapError> {
+        Ok(Self { map: F14Map::with_hasher_in(hasher, alloc)? })
+    }
+
+    /// 使用指定容量、哈希构建器和分配器创建 F14Set
+    pub fn with_capacity_and_hasher_in(capacity: usize, hasher: S, alloc: A) -> Result<Self, MapError> {
+        Ok(Self { map: F14Map::with_capacity_and_hasher_in(capacity, hasher, alloc)? })
+    }
+}
+
+impl<T, S, P, A> F14Set<T, S, P, A>
+where
+    T: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 预留至少 `additional` 个元素的空间
+    ///
+    /// # Panics
+    /// 容量溢出或分配失败时 panic，需要处理错误时使用 [`F14Set::try_reserve`]。
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    /// 预留至少 `additional` 个元素的空间，失败时返回错误且集合保持不变
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), MapError> {
+        self.map.try_reserve(additional)
+    }
+
+    /// 尽可能缩小容量
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    /// 缩小容量，但至少保留 `min_capacity` 个元素的空间
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.map.shrink_to(min_capacity);
+    }
+
+    /// 插入元素，元素已存在时返回 `false` 且保留原元素
+    pub fn insert(&mut self, value: T) -> Result<bool, MapError> {
+        Ok(self.map.insert(value, ())?.is_none())
+    }
+
+    /// 插入元素，元素已存在时替换并返回原元素
+    pub fn replace(&mut self, value: T) -> Result<Option<T>, MapError> {
+        match self.map.try_entry(value)? {
+            Entry::Occupied(entry) => Ok(Some(entry.replace_key())),
+            Entry::Vacant(entry) => {
+                entry.try_insert_entry(())?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// 检查元素是否存在
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// 获取集合中与 `value` 相等的元素
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get_key_value(value).map(|(key, _)| key)
+    }
+
+    /// 移除元素，返回元素是否存在
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// 移除并返回集合中与 `value` 相等的元素
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove_entry(value).map(|(key, _)| key)
+    }
+}
+
+impl<T, S, P, A> F14Set<T, S, P, A>
+where
+    T: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 差集：在 `self` 中但不在 `other` 中的元素
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, T, S, P, A> {
+        Difference { iter: self.iter(), other }
+    }
+
+    /// 对称差：只在其中一个集合中的元素
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<'a, T, S, P, A> {
+        SymmetricDifference { iter: self.difference(other).chain(other.difference(self)) }
+    }
+
+    /// 交集：同时在两个集合中的元素（遍历较小的集合）
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, T, S, P, A> {
+        if self.len() <= other.len() {
+            Intersection { iter: self.iter(), other }
+        } else {
+            Intersection { iter: other.iter(), other: self }
+        }
+    }
+
+    /// 并集：遍历较大的集合，再补上较小集合中独有的元素
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, T, S, P, A> {
+        if self.len() >= other.len() {
+            Union { iter: self.iter().chain(other.difference(self)) }
+        } else {
+            Union { iter: other.iter().chain(self.difference(other)) }
+        }
+    }
+
+    /// 检查两个集合是否没有公共元素
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).next().is_none()
+    }
+
+    /// 检查 `self` 的所有元素是否都在 `other` 中
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.iter().all(|value| other.contains(value))
+    }
+
+    /// 检查 `other` 的所有元素是否都在 `self` 中
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+}
+
+impl<T, S, P, A> IntoIterator for F14Set<T, S, P, A>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    type Item = T;
+    type IntoIter = IntoIter<T, P, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { inner: self.map.into_iter() }
+    }
+}
+
+impl<'a, T, S, P: StoragePolicy, A: Allocator + Clone> IntoIterator for &'a F14Set<T, S, P, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, P, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, S, P, A> std::fmt::Debug for F14Set<T, S, P, A>
+where
+    T: std::fmt::Debug,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set()
+            .entries(self.iter())
+            .finish()
+    }
+}
+
+impl<T, S, P, A> Default for F14Set<T, S, P, A>
+where
+    S: BuildHasherExt + Default,
+    P: StoragePolicy,
+    A: Allocator + Clone + Default,
+{
+    fn default() -> Self {
+        Self { map: F14Map::default() }
+    }
+}
+
+/// 集合的不可变迭代器
+pub struct Iter<'a, T: 'a, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone + 'a,
+{
+    inner: iterators::Iter<'a, T, (), P, A>,
+}
+
+impl<T, P: StoragePolicy, A: Allocator + Clone> Clone for Iter<'_, T, P, A> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<'a, T, P: StoragePolicy, A: Allocator + Clone> Iterator for Iter<'a, T, P, A> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(value, _)| value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, P: StoragePolicy, A: Allocator + Clone> ExactSizeIterator for Iter<'_, T, P, A> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T, P: StoragePolicy, A: Allocator + Clone> FusedIterator for Iter<'_, T, P, A> {}
+
+/// 集合的消耗迭代器
+pub struct IntoIter<T, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    inner: iterators::IntoIter<T, (), P, A>,
+}
+
+impl<T, P: StoragePolicy, A: Allocator + Clone> Iterator for IntoIter<T, P, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(value, _)| value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, P: StoragePolicy, A: Allocator + Clone> ExactSizeIterator for IntoIter<T, P, A> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T, P: StoragePolicy, A: Allocator + Clone> FusedIterator for IntoIter<T, P, A> {}
+
+/// 差集的惰性迭代器，由 [`F14Set::difference`] 创建
+pub struct Difference<'a, T: 'a, S, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone + 'a,
+{
+    iter: Iter<'a, T, P, A>,
+    other: &'a F14Set<T, S, P, A>,
+}
+
+impl<T, S, P: StoragePolicy, A: Allocator + Clone> Clone for Difference<'_, T, S, P, A> {
+    fn clone(&self) -> Self {
+        Self { iter: self.iter.clone(), other: self.other }
+    }
+}
+
+impl<'a, T, S, P, A> Iterator for Difference<'a, T, S, P, A>
+where
+    T: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|value| !other.contains(*value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<T, S, P, A> FusedIterator for Difference<'_, T, S, P, A>
+where
+    T: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+}
+
+/// 交集的惰性迭代器，由 [`F14Set::intersection`] 创建
+pub struct Intersection<'a, T: 'a, S, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone + 'a,
+{
+    iter: Iter<'a, T, P, A>,
+    other: &'a F14Set<T, S, P, A>,
+}
+
+impl<T, S, P: StoragePolicy, A: Allocator + Clone> Clone for Intersection<'_, T, S, P, A> {
+    fn clone(&self) -> Self {
+        Self { iter: self.iter.clone(), other: self.other }
+    }
+}
+
+impl<'a, T, S, P, A> Iterator for Intersection<'a, T, S, P, A>
+where
+    T: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|value| other.contains(*value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<T, S, P, A> FusedIterator for Intersection<'_, T, S, P, A>
+where
+    T: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+}
+
+/// 对称差与并集内部的组合迭代器
+type SymmetricDifferenceInner<'a, T, S, P, A> = Chain<Difference<'a, T, S, P, A>, Difference<'a, T, S, P, A>>;
+type UnionInner<'a, T, S, P, A> = Chain<Iter<'a, T, P, A>, Difference<'a, T, S, P, A>>;
+
+/// 对称差的惰性迭代器，由 [`F14Set::symmetric_difference`] 创建
+pub struct SymmetricDifference<'a, T: 'a, S, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone + 'a,
+{
+    iter: SymmetricDifferenceInner<'a, T, S, P, A>,
+}
+
+impl<T, S, P: StoragePolicy, A: Allocator + Clone> Clone for SymmetricDifference<'_, T, S, P, A> {
+    fn clone(&self) -> Self {
+        Self { iter: self.iter.clone() }
+    }
+}
+
+impl<'a, T, S, P, A> Iterator for SymmetricDifference<'a, T, S, P, A>
+where
+    T: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S, P, A> FusedIterator for SymmetricDifference<'_, T, S, P, A>
+where
+    T: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+}
+
+/// 并集的惰性迭代器，由 [`F14Set::union`] 创建
+pub struct Union<'a, T: 'a, S, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone + 'a,
+{
+    iter: UnionInner<'a, T, S, P, A>,
+}
+
+impl<T, S, P: StoragePolicy, A: Allocator + Clone> Clone for Union<'_, T, S, P, A> {
+    fn clone(&self) -> Self {
+        Self { iter: self.iter.clone() }
+    }
+}
+
+impl<'a, T, S, P, A> Iterator for Union<'a, T, S, P, A>
+where
+    T: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S, P, A> FusedIterator for Union<'_, T, S, P, A>
+where
+    T: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+}
@@ -0,0 +1,263 @@
+//! 渐进式扩容映射
+//!
+//! [`F14Map`](crate::F14Map) 扩容时一次性把所有元素搬到新表，元素很多时会造成明显的
+//! 延迟尖刺。[`IncrementalF14Map`] 在需要扩容时只分配新表，旧表保留下来；
+//! 之后每次插入、删除或可变查找顺带迁移最多 [`MIGRATE_CHUNKS`] 组
+//! （每组 [`CHUNK_SIZE`] 个元素），直到旧表清空。
+//!
+//! 只读查找（[`get`](IncrementalF14Map::get)、[`contains_key`](IncrementalF14Map::contains_key)
+//! 和迭代）只持有 `&self`，不会推进迁移；以读为主的场景应在空闲时调用
+//! [`migrate_step`](IncrementalF14Map::migrate_step)，否则旧表会一直保留，
+//! 未命中的查找也要多检查一张表。
+//!
+//! 迁移期间每个键只存在于其中一张表：新键总是写入新表，旧表中的键原地更新，
+//! 查找依次检查新表和旧表。[`IncrementalF14Map::finish_resize`] 可以立即完成迁移。
+//!
+//! 新表容量是旧表的两倍，而迁移在新表写满之前一定能完成，因此迁移本身不会分配内存。
+
+use crate::{error::MapError, f14_map::F14VectorMap, simd_utils::CHUNK_SIZE, traits::BuildHasherExt};
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    hash::Hash,
+    iter::{Chain, FusedIterator},
+    mem, slice,
+};
+
+/// 每次操作最多迁移的分组数
+pub const MIGRATE_CHUNKS: usize = 2;
+
+/// 渐进式扩容的哈希表
+pub struct IncrementalF14Map<K, V, S = RandomState> {
+    // 新键写入的表
+    current: F14VectorMap<K, V, S>,
+    // 迁移中的旧表，迁移完成后为 `None`
+    old: Option<F14VectorMap<K, V, S>>,
+}
+
+impl<K, V, S> IncrementalF14Map<K, V, S>
+where
+    S: BuildHasherExt + Default,
+{
+    /// 创建一个新的映射
+    pub fn new() -> Result<Self, MapError> {
+        Self::with_hasher(S::default())
+    }
+
+    /// 创建指定容量的映射
+    pub fn with_capacity(capacity: usize) -> Result<Self, MapError> {
+        Self::with_capacity_and_hasher(capacity, S::default())
+    }
+}
+
+impl<K, V, S> IncrementalF14Map<K, V, S>
+where
+    S: BuildHasherExt,
+{
+    /// 使用指定的哈希构建器创建映射
+    pub fn with_hasher(hasher: S) -> Result<Self, MapError> {
+        Self::with_capacity_and_hasher(0, hasher)
+    }
+
+    /// 使用指定容量和哈希构建器创建映射
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Result<Self, MapError> {
+        Ok(Self { current: F14VectorMap::with_capacity_and_hasher(capacity, hasher)?, old: None })
+    }
+
+    /// 元素数量（两张表之和）
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.current.len() + self.old.as_ref().map_or(0, |old| old.len())
+    }
+
+    /// 检查是否为空
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 新表的容量
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.current.capacity()
+    }
+
+    /// 是否正在迁移
+    #[inline]
+    pub fn is_resizing(&self) -> bool {
+        self.old.is_some()
+    }
+
+    /// 旧表中尚未迁移的元素数量
+    #[inline]
+    pub fn pending_migration(&self) -> usize {
+        self.old.as_ref().map_or(0, |old| old.len())
+    }
+
+    /// 迭代所有键值对（先新表后旧表）
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let old = self.old.as_ref().map_or(&[][..], |old| old.as_slice());
+        Iter { inner: self.current.as_slice().iter().chain(old.iter()) }
+    }
+
+    /// 清空映射，放弃未完成的迁移
+    pub fn clear(&mut self) {
+        self.old = None;
+        self.current.clear();
+    }
+}
+
+impl<K, V, S> IncrementalF14Map<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasherExt + Clone,
+{
+    /// 分配两倍容量的新表，当前表转为旧表
+    fn start_resize(&mut self) -> Result<(), MapError> {
+        debug_assert!(self.old.is_none());
+        let capacity = if self.current.capacity() == 0 { CHUNK_SIZE } else { self.current.capacity() * 2 };
+        let next = F14VectorMap::with_capacity_and_hasher(capacity, self.current.hasher().clone())?;
+        let old = mem::replace(&mut self.current, next);
+        if !old.is_empty() {
+            info!("开始渐进扩容: len={}, 旧容量={}, 新容量={}", old.len(), old.capacity(), capacity);
+            self.old = Some(old);
+        }
+        Ok(())
+    }
+
+    /// 从旧表末尾迁移最多 `chunks` 组元素，返回是否已完成迁移
+    pub fn resize_step(&mut self, chunks: usize) -> bool {
+        let Some(old) = self.old.as_mut() else {
+            return true;
+        };
+        for _ in 0..chunks.saturating_mul(CHUNK_SIZE) {
+            let Some(last) = old.len().checked_sub(1) else {
+                break;
+            };
+            // 新表有两倍于旧表的容量，迁移的写入不会扩容或分配；
+            // 两张表共享同一哈希构建器的克隆，沿用旧表算出的哈希
+            let (hash, (key, value)) = old.swap_remove_index_hashed(last);
+            self.current.insert_unique_no_grow(hash, key, value);
+        }
+        if old.is_empty() {
+            info!("渐进扩容完成: len={}, capacity={}", self.current.len(), self.current.capacity());
+            self.old = None;
+        }
+        self.old.is_none()
+    }
+
+    /// 推进一次迁移，与写操作顺带迁移的数量相同，返回是否已完成迁移
+    ///
+    /// 只读查找不会推进迁移，只读的调用方可以用它分摊迁移的开销。
+    pub fn migrate_step(&mut self) -> bool {
+        self.resize_step(MIGRATE_CHUNKS)
+    }
+
+    /// 立即完成迁移
+    pub fn finish_resize(&mut self) {
+        while !self.resize_step(usize::MAX / CHUNK_SIZE) {}
+    }
+
+    /// 插入键值对，返回旧值
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, MapError> {
+        self.resize_step(MIGRATE_CHUNKS);
+        let hash = self.current.hash_of(&key);
+        if let Some(slot) = self.old.as_mut().and_then(|old| old.get_mut_with_hash(hash, &key)) {
+            return Ok(Some(mem::replace(slot, value)));
+        }
+        // 覆盖已有的键不占用新槽位，原地更新，即使新表已到负载上限也不扩容
+        if let Some(slot) = self.current.get_mut_with_hash(hash, &key) {
+            return Ok(Some(mem::replace(slot, value)));
+        }
+        if self.current.needs_grow() {
+            // 旧表一定已经迁移完毕；保险起见先完成迁移再开始下一轮
+            self.finish_resize();
+            self.start_resize()?;
+        }
+        self.current.insert_unique_no_grow(hash, key, value);
+        Ok(None)
+    }
+
+    /// 获取值
+    ///
+    /// 只读访问，不推进迁移，见 [`migrate_step`](Self::migrate_step)。
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        // 两张表共享同一哈希构建器的克隆，哈希只需计算一次
+        let hash = self.current.hash_of(key);
+        self.current
+            .get_with_hash(hash, key)
+            .or_else(|| self.old.as_ref()?.get_with_hash(hash, key))
+    }
+
+    /// 获取值的可变引用（顺带推进迁移）
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.resize_step(MIGRATE_CHUNKS);
+        let hash = self.current.hash_of(key);
+        match self.current.get_mut_with_hash(hash, key) {
+            Some(value) => Some(value),
+            None => self.old.as_mut()?.get_mut_with_hash(hash, key),
+        }
+    }
+
+    /// 检查键是否存在
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// 移除键，返回值（顺带推进迁移）
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.resize_step(MIGRATE_CHUNKS);
+        let hash = self.current.hash_of(key);
+        self.current
+            .remove_with_hash(hash, key)
+            .or_else(|| self.old.as_mut()?.remove_with_hash(hash, key))
+    }
+}
+
+impl<K: std::fmt::Debug, V: std::fmt::Debug, S: BuildHasherExt> std::fmt::Debug for IncrementalF14Map<K, V, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// 新表与旧表的键值对依次相接
+type PairChain<'a, K, V> = Chain<slice::Iter<'a, (K, V)>, slice::Iter<'a, (K, V)>>;
+
+/// 键值对迭代器，由 [`IncrementalF14Map::iter`] 创建
+pub struct Iter<'a, K, V> {
+    inner: PairChain<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, value)| (key, value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
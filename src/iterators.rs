@@ -1,140 +1,503 @@
-//! F14VectorMap 迭代器实现
+//! F14Map 迭代器实现
+//!
+//! 迭代顺序由存储策略决定：向量策略直接遍历稠密数组，
+//! 其他策略按槽位顺序遍历分组表中的 FULL 槽位。
 
-use super::f14_map::{F14VectorMap, SlotState};
-use std::marker::PhantomData;
+use crate::{
+    allocator::{AlignedAllocator, Allocator},
+    f14_map::F14Map,
+    policy::{StoragePolicy, VectorPolicy},
+    simd_utils::CHUNK_SIZE,
+    storage::RawStorage,
+    traits::BuildHasherExt,
+};
+use std::{collections::hash_map::RandomState, hash::Hash, iter::FusedIterator};
+
+/// 存储策略提供的底层迭代器
+type RawIter<'a, K, V, P, A> = <<P as StoragePolicy>::Storage<K, V, A> as RawStorage<K, V, A>>::Iter<'a>;
+type RawIterMut<'a, K, V, P, A> =
+    <<P as StoragePolicy>::Storage<K, V, A> as RawStorage<K, V, A>>::IterMut<'a>;
+type RawIntoIter<K, V, P, A> = <<P as StoragePolicy>::Storage<K, V, A> as RawStorage<K, V, A>>::IntoIter;
+type RawDrain<'a, K, V, P, A> = <<P as StoragePolicy>::Storage<K, V, A> as RawStorage<K, V, A>>::Drain<'a>;
 
 /// 不可变迭代器
-pub struct Iter<'a, K, V, S> {
-    map: &'a F14VectorMap<K, V, S>,
-    current: usize,
+pub struct Iter<'a, K: 'a, V: 'a, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone + 'a,
+{
+    inner: RawIter<'a, K, V, P, A>,
+}
+
+impl<'a, K, V, P: StoragePolicy, A: Allocator + Clone> Iter<'a, K, V, P, A> {
+    pub(crate) fn new(inner: RawIter<'a, K, V, P, A>) -> Self {
+        Self { inner }
+    }
 }
 
-impl<'a, K, V, S> Iter<'a, K, V, S> {
-    pub(crate) fn new(map: &'a F14VectorMap<K, V, S>) -> Self {
-        Self { map, current: 0 }
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Clone for Iter<'_, K, V, P, A> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
     }
 }
 
-impl<'a, K, V, S> Iterator for Iter<'a, K, V, S> {
+impl<'a, K, V, P: StoragePolicy, A: Allocator + Clone> Iterator for Iter<'a, K, V, P, A> {
     type Item = (&'a K, &'a V);
-    
+
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        while self.current < self.map.capacity() {
-            let index = self.current;
-            self.current += 1;
-            
-            // 只处理FULL状态的槽位
-            if let SlotState::Full = self.map.slot_state(index) {
-                unsafe {
-                    // 修复：直接访问KeyValuePair字段
-                    let entry = self.map.get_entry(index);
-                    let key = &*entry.key.as_ptr();
-                    let value = &*entry.value.as_ptr();
-                    return Some((key, value));
-                }
-            }
-        }
-        
-        None
+        self.inner.next().map(|(key, value)| (key, value))
     }
-    
+
+    #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.map.len(), Some(self.map.len()))
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.inner.fold(init, |acc, (key, value)| f(acc, (key, value)))
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> DoubleEndedIterator for Iter<'_, K, V, P, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, value)| (key, value))
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> ExactSizeIterator for Iter<'_, K, V, P, A> {
+    fn len(&self) -> usize {
+        self.inner.len()
     }
 }
 
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> FusedIterator for Iter<'_, K, V, P, A> {}
+
 /// 可变迭代器
-pub struct IterMut<'a, K, V, S> {
-    map: *mut F14VectorMap<K, V, S>,
-    current: usize,
-    _marker: PhantomData<&'a mut F14VectorMap<K, V, S>>,
-}
-
-impl<'a, K, V, S> IterMut<'a, K, V, S> {
-    pub(crate) fn new(map: &'a mut F14VectorMap<K, V, S>) -> Self {
-        Self {
-            map: map as *mut F14VectorMap<K, V, S>,
-            current: 0,
-            _marker: PhantomData,
-        }
+///
+/// 只能修改值：键决定了元素在分组表中的位置。
+pub struct IterMut<'a, K: 'a, V: 'a, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone + 'a,
+{
+    inner: RawIterMut<'a, K, V, P, A>,
+}
+
+impl<'a, K, V, P: StoragePolicy, A: Allocator + Clone> IterMut<'a, K, V, P, A> {
+    pub(crate) fn new(inner: RawIterMut<'a, K, V, P, A>) -> Self {
+        Self { inner }
     }
 }
 
-impl<'a, K, V, S> Iterator for IterMut<'a, K, V, S> {
-    type Item = (&'a mut K, &'a mut V);
-    
+impl<'a, K, V, P: StoragePolicy, A: Allocator + Clone> Iterator for IterMut<'a, K, V, P, A> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        // 安全：我们保证每次调用next时只提供一个可变引用
-        unsafe {
-            let map = &mut *self.map;
-            
-            while self.current < map.capacity() {
-                let index = self.current;
-                self.current += 1;
-                
-                // 只返回FULL状态的槽位
-                if let SlotState::Full = map.slot_state(index) {
-                    // 修复：直接访问KeyValuePair字段
-                    let entry = map.get_entry_mut(index);
-                    let key = &mut *entry.key.as_mut_ptr();
-                    let value = &mut *entry.value.as_mut_ptr();
-                    return Some((key, value));
-                }
-            }
-            
-            None
-        }
+        self.inner.next().map(|(key, value)| (&*key, value))
     }
-    
+
+    #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        unsafe {
-            let map = &*self.map;
-            (map.len(), Some(map.len()))
-        }
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> DoubleEndedIterator for IterMut<'_, K, V, P, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, value)| (&*key, value))
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> ExactSizeIterator for IterMut<'_, K, V, P, A> {
+    fn len(&self) -> usize {
+        self.inner.len()
     }
 }
 
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> FusedIterator for IterMut<'_, K, V, P, A> {}
+
 /// 消耗迭代器
-pub struct IntoIter<K, V, S> {
-    map: F14VectorMap<K, V, S>,
-    current: usize,
+pub struct IntoIter<K, V, P: StoragePolicy = VectorPolicy, A: Allocator + Clone = AlignedAllocator> {
+    inner: RawIntoIter<K, V, P, A>,
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> IntoIter<K, V, P, A> {
+    pub(crate) fn new(inner: RawIntoIter<K, V, P, A>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Iterator for IntoIter<K, V, P, A> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
-impl<K, V, S> IntoIter<K, V, S> {
-    pub(crate) fn new(map: F14VectorMap<K, V, S>) -> Self {
-        Self { map, current: 0 }
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> DoubleEndedIterator for IntoIter<K, V, P, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
     }
 }
 
-impl<K, V, S> Iterator for IntoIter<K, V, S> {
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> ExactSizeIterator for IntoIter<K, V, P, A> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> FusedIterator for IntoIter<K, V, P, A> {}
+
+/// 排空迭代器，由 [`F14Map::drain`] 创建
+pub struct Drain<'a, K: 'a, V: 'a, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone + 'a,
+{
+    inner: RawDrain<'a, K, V, P, A>,
+}
+
+impl<'a, K, V, P: StoragePolicy, A: Allocator + Clone> Drain<'a, K, V, P, A> {
+    pub(crate) fn new(inner: RawDrain<'a, K, V, P, A>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Iterator for Drain<'_, K, V, P, A> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> DoubleEndedIterator for Drain<'_, K, V, P, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> ExactSizeIterator for Drain<'_, K, V, P, A> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> FusedIterator for Drain<'_, K, V, P, A> {}
+
+/// 按谓词移出键值对的迭代器，由 [`F14Map::extract_if`] 创建
+///
+/// 逐组取 FULL 槽位位掩码，再按最低位依次检查；删除只会把当前槽位置为 EMPTY，
+/// 不影响同组尚未检查的槽位，因此每个键值对恰好检查一次。
+pub struct ExtractIf<'a, K, V, F, S = RandomState, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    map: &'a mut F14Map<K, V, S, P, A>,
+    pred: F,
+    // 下一个待取位掩码的分组
+    next_group: usize,
+    // 当前分组起始槽位
+    group_start: usize,
+    // 当前分组中尚未检查的 FULL 槽位
+    mask: u16,
+}
+
+impl<'a, K, V, F, S, P: StoragePolicy, A: Allocator + Clone> ExtractIf<'a, K, V, F, S, P, A> {
+    pub(crate) fn new(map: &'a mut F14Map<K, V, S, P, A>, pred: F) -> Self {
+        Self { map, pred, next_group: 0, group_start: 0, mask: 0 }
+    }
+}
+
+impl<K, V, F, S, P, A> Iterator for ExtractIf<'_, K, V, F, S, P, A>
+where
+    K: Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+    F: FnMut(&K, &mut V) -> bool,
+{
     type Item = (K, V);
-    
+
     fn next(&mut self) -> Option<Self::Item> {
-        while self.current < self.map.capacity() {
-            let index = self.current;
-            self.current += 1;
-            
-            // 只处理FULL状态的槽位
-            if let SlotState::Full = self.map.slot_state(index) {
-                unsafe {
-                    // 获取元素后标记为空
-                    let (key, value) = self.map.replace_slot_state(index, SlotState::Empty);
-                    // 不再需要手动减少长度，因为 replace_slot_state 已经处理了
-                    return Some((key, value));
+        loop {
+            while self.mask != 0 {
+                let slot = self.group_start + self.mask.trailing_zeros() as usize;
+                self.mask &= self.mask - 1;
+                let (key, value) = self.map.pair_at_mut(slot);
+                if (self.pred)(key, value) {
+                    return Some(self.map.take_slot(slot));
                 }
             }
+            if self.next_group == self.map.group_count() {
+                return None;
+            }
+            self.group_start = self.next_group * CHUNK_SIZE;
+            self.mask = self.map.full_mask(self.next_group);
+            self.next_group += 1;
         }
-        
-        None
     }
-    
+
+    #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.map.len(), Some(self.map.len()))
+        (0, Some(self.map.len()))
+    }
+}
+
+impl<K, V, F, S, P, A> FusedIterator for ExtractIf<'_, K, V, F, S, P, A>
+where
+    K: Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+    F: FnMut(&K, &mut V) -> bool,
+{
+}
+
+/// 键迭代器，由 [`F14Map::keys`] 创建
+pub struct Keys<'a, K: 'a, V: 'a, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone + 'a,
+{
+    inner: Iter<'a, K, V, P, A>,
+}
+
+impl<'a, K, V, P: StoragePolicy, A: Allocator + Clone> Keys<'a, K, V, P, A> {
+    pub(crate) fn new(inner: Iter<'a, K, V, P, A>) -> Self {
+        Self { inner }
     }
 }
 
-impl<K, V, S> ExactSizeIterator for IntoIter<K, V, S> {
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Clone for Keys<'_, K, V, P, A> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<'a, K, V, P: StoragePolicy, A: Allocator + Clone> Iterator for Keys<'a, K, V, P, A> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> DoubleEndedIterator for Keys<'_, K, V, P, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> ExactSizeIterator for Keys<'_, K, V, P, A> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> FusedIterator for Keys<'_, K, V, P, A> {}
+
+/// 值迭代器，由 [`F14Map::values`] 创建
+pub struct Values<'a, K: 'a, V: 'a, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone + 'a,
+{
+    inner: Iter<'a, K, V, P, A>,
+}
+
+impl<'a, K, V, P: StoragePolicy, A: Allocator + Clone> Values<'a, K, V, P, A> {
+    pub(crate) fn new(inner: Iter<'a, K, V, P, A>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Clone for Values<'_, K, V, P, A> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<'a, K, V, P: StoragePolicy, A: Allocator + Clone> Iterator for Values<'a, K, V, P, A> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> DoubleEndedIterator for Values<'_, K, V, P, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> ExactSizeIterator for Values<'_, K, V, P, A> {
     fn len(&self) -> usize {
-        self.map.len()
+        self.inner.len()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> FusedIterator for Values<'_, K, V, P, A> {}
+
+/// 可变值迭代器，由 [`F14Map::values_mut`] 创建
+pub struct ValuesMut<'a, K: 'a, V: 'a, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone + 'a,
+{
+    inner: IterMut<'a, K, V, P, A>,
+}
+
+impl<'a, K, V, P: StoragePolicy, A: Allocator + Clone> ValuesMut<'a, K, V, P, A> {
+    pub(crate) fn new(inner: IterMut<'a, K, V, P, A>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, K, V, P: StoragePolicy, A: Allocator + Clone> Iterator for ValuesMut<'a, K, V, P, A> {
+    type Item = &'a mut V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
     }
-}
\ No newline at end of file
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> DoubleEndedIterator for ValuesMut<'_, K, V, P, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> ExactSizeIterator for ValuesMut<'_, K, V, P, A> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> FusedIterator for ValuesMut<'_, K, V, P, A> {}
+
+/// 移出键的消耗迭代器，由 [`F14Map::into_keys`] 创建
+pub struct IntoKeys<K, V, P: StoragePolicy = VectorPolicy, A: Allocator + Clone = AlignedAllocator> {
+    inner: IntoIter<K, V, P, A>,
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> IntoKeys<K, V, P, A> {
+    pub(crate) fn new(inner: IntoIter<K, V, P, A>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Iterator for IntoKeys<K, V, P, A> {
+    type Item = K;
+
+    #[inline]
+    fn next(&mut self) -> Option<K> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> DoubleEndedIterator for IntoKeys<K, V, P, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<K> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> ExactSizeIterator for IntoKeys<K, V, P, A> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> FusedIterator for IntoKeys<K, V, P, A> {}
+
+/// 移出值的消耗迭代器，由 [`F14Map::into_values`] 创建
+pub struct IntoValues<K, V, P: StoragePolicy = VectorPolicy, A: Allocator + Clone = AlignedAllocator> {
+    inner: IntoIter<K, V, P, A>,
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> IntoValues<K, V, P, A> {
+    pub(crate) fn new(inner: IntoIter<K, V, P, A>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Iterator for IntoValues<K, V, P, A> {
+    type Item = V;
+
+    #[inline]
+    fn next(&mut self) -> Option<V> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> DoubleEndedIterator for IntoValues<K, V, P, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<V> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> ExactSizeIterator for IntoValues<K, V, P, A> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> FusedIterator for IntoValues<K, V, P, A> {}
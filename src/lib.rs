@@ -10,13 +10,55 @@
 
 
 
+// 扩容、重建等低频路径的诊断信息：启用 `log` 特性时转发给 `log::info!`，
+// 否则不产生任何输出（参数仍参与类型检查）
+#[cfg(feature = "log")]
+macro_rules! info {
+    ($($arg:tt)*) => { log::info!($($arg)*) };
+}
+#[cfg(not(feature = "log"))]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
 pub mod error;
+pub mod entry;
 pub mod f14_map;
+pub mod f14_set;
 pub mod iterators;
 pub mod simd_utils;
 pub mod traits;
 pub mod allocator;
 pub mod probe_strategy;
+pub mod policy;
+pub mod snapshot;
+pub mod bounded;
+pub mod expiring;
+pub mod incremental;
+#[cfg(feature = "concurrent")]
+pub mod concurrent;
+#[cfg(feature = "concurrent")]
+pub mod seqlock;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+mod raw_table;
+mod dense_array;
+mod storage;
 // 公共导出
-pub use f14_map::F14VectorMap;
-pub use error::MapError;
\ No newline at end of file
+pub use f14_map::{BatchReport, F14FastMap, F14Map, F14NodeMap, F14ValueMap, F14VectorMap};
+pub use f14_set::{F14FastSet, F14NodeSet, F14Set, F14ValueSet, F14VectorSet};
+pub use error::MapError;
+pub use snapshot::SnapshotF14Map;
+pub use bounded::{BoundedF14Map, EvictionPolicy};
+pub use expiring::ExpiringF14Map;
+pub use incremental::IncrementalF14Map;
+#[cfg(feature = "concurrent")]
+pub use concurrent::ConcurrentF14Map;
+#[cfg(feature = "concurrent")]
+pub use seqlock::SeqLockF14Map;
+#[cfg(feature = "rayon")]
+pub use parallel::{ParDrain, ParIter, ParIterMut};
\ No newline at end of file
@@ -0,0 +1,501 @@
+//! 基于 rayon 的并行迭代与批量操作（`rayon` 特性）
+//!
+//! 分组表由 `group_count` 个 `CHUNK_SIZE` 槽位的分组组成，并行生产者按分组区间对半拆分，
+//! 区间内逐组读取 FULL 位掩码产出元素，所有存储策略共用同一套拆分逻辑。
+//! 插入与删除需要独占分组表，因此 [`F14Map::try_par_extend`] 与 [`F14Map::par_retain`]
+//! 分两阶段：先并行计算哈希或判断谓词，再顺序修改分组表。
+
+use crate::{
+    allocator::{AlignedAllocator, Allocator},
+    error::MapError,
+    f14_map::{make_hash, F14Map},
+    policy::{StoragePolicy, VectorPolicy},
+    simd_utils::CHUNK_SIZE,
+    storage::RawStorage,
+    traits::BuildHasherExt,
+};
+use rayon::iter::{
+    plumbing::{bridge_unindexed, Folder, UnindexedConsumer, UnindexedProducer},
+    IntoParallelIterator, ParallelExtend, ParallelIterator,
+};
+use std::{collections::LinkedList, hash::Hash, marker::PhantomData, ptr::NonNull};
+
+/// 策略对应的底层存储
+type Storage<K, V, P, A> = <P as StoragePolicy>::Storage<K, V, A>;
+
+/// 从 FULL 槽位取出元素的方式
+///
+/// 实现者只保存指向存储的指针，可以复制到各个工作线程；生命周期由外层并行迭代器绑定。
+trait SlotSource: Copy + Send + Sync {
+    type Item: Send;
+
+    /// 生产者未产出完就被析构时，剩余槽位是否需要逐个取出并析构
+    const NEEDS_RELEASE: bool;
+
+    /// 分组中 FULL 槽位的位掩码
+    fn full_mask(&self, group_index: usize) -> u16;
+
+    /// 取出槽位对应的元素
+    ///
+    /// # Safety
+    /// 槽位必须为 FULL，且在迭代期间只被取出一次。
+    unsafe fn take(&self, slot: usize) -> Self::Item;
+}
+
+/// 按分组区间 `[start, end)` 拆分的生产者
+struct GroupProducer<T: SlotSource> {
+    source: T,
+    start: usize,
+    end: usize,
+}
+
+impl<T: SlotSource> GroupProducer<T> {
+    fn new(source: T, group_count: usize) -> Self {
+        Self { source, start: 0, end: group_count }
+    }
+
+    /// 析构分组中 `mask` 标记的尚未产出的元素
+    fn release(&self, group_index: usize, mut mask: u16) {
+        while mask != 0 {
+            let slot = group_index * CHUNK_SIZE + mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            drop(unsafe { self.source.take(slot) });
+        }
+    }
+}
+
+impl<T: SlotSource> UnindexedProducer for GroupProducer<T> {
+    type Item = T::Item;
+
+    fn split(mut self) -> (Self, Option<Self>) {
+        let groups = self.end - self.start;
+        if groups <= 1 {
+            return (self, None);
+        }
+        let mid = self.start + groups / 2;
+        let right = Self { source: self.source, start: mid, end: self.end };
+        self.end = mid;
+        (self, Some(right))
+    }
+
+    fn fold_with<F>(mut self, mut folder: F) -> F
+    where
+        F: Folder<Self::Item>,
+    {
+        while self.start < self.end {
+            let group_index = self.start;
+            let mut mask = self.source.full_mask(group_index);
+            // 先越过当前分组：消费者 panic 时本组余下的元素被泄漏，而不是被析构两次
+            self.start += 1;
+            while mask != 0 {
+                let slot = group_index * CHUNK_SIZE + mask.trailing_zeros() as usize;
+                mask &= mask - 1;
+                folder = folder.consume(unsafe { self.source.take(slot) });
+                if folder.full() {
+                    // 之后的分组由 Drop 释放
+                    if T::NEEDS_RELEASE {
+                        self.release(group_index, mask);
+                    }
+                    return folder;
+                }
+            }
+        }
+        folder
+    }
+}
+
+impl<T: SlotSource> Drop for GroupProducer<T> {
+    fn drop(&mut self) {
+        if T::NEEDS_RELEASE {
+            for group_index in self.start..self.end {
+                self.release(group_index, self.source.full_mask(group_index));
+            }
+        }
+    }
+}
+
+/// 产出 `(&K, &V)`
+struct PairRefs<'a, K, V, P: StoragePolicy, A: Allocator + Clone> {
+    storage: NonNull<Storage<K, V, P, A>>,
+    marker: PhantomData<&'a (K, V)>,
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Clone for PairRefs<'_, K, V, P, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Copy for PairRefs<'_, K, V, P, A> {}
+
+// 只读取控制字节与键值对，与 `&F14Map` 在线程间共享的条件相同
+unsafe impl<K: Sync, V: Sync, P: StoragePolicy, A: Allocator + Clone> Send for PairRefs<'_, K, V, P, A> {}
+unsafe impl<K: Sync, V: Sync, P: StoragePolicy, A: Allocator + Clone> Sync for PairRefs<'_, K, V, P, A> {}
+
+impl<'a, K: Sync + 'a, V: Sync + 'a, P: StoragePolicy, A: Allocator + Clone> SlotSource
+    for PairRefs<'a, K, V, P, A>
+{
+    type Item = (&'a K, &'a V);
+
+    const NEEDS_RELEASE: bool = false;
+
+    #[inline]
+    fn full_mask(&self, group_index: usize) -> u16 {
+        unsafe { self.storage.as_ref() }.full_mask(group_index)
+    }
+
+    #[inline]
+    unsafe fn take(&self, slot: usize) -> Self::Item {
+        let pair = NonNull::from(unsafe { self.storage.as_ref() }.pair(slot));
+        let (key, value) = unsafe { &*pair.as_ptr() };
+        (key, value)
+    }
+}
+
+/// 产出 `(&K, &mut V)`，各槽位只被一个线程访问
+struct PairMuts<'a, K, V, P: StoragePolicy, A: Allocator + Clone> {
+    storage: NonNull<Storage<K, V, P, A>>,
+    marker: PhantomData<&'a mut (K, V)>,
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Clone for PairMuts<'_, K, V, P, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Copy for PairMuts<'_, K, V, P, A> {}
+
+// 键只被共享，值被各线程独占修改
+unsafe impl<K: Sync, V: Send, P: StoragePolicy, A: Allocator + Clone> Send for PairMuts<'_, K, V, P, A> {}
+unsafe impl<K: Sync, V: Send, P: StoragePolicy, A: Allocator + Clone> Sync for PairMuts<'_, K, V, P, A> {}
+
+impl<'a, K: Sync + 'a, V: Send + 'a, P: StoragePolicy, A: Allocator + Clone> SlotSource
+    for PairMuts<'a, K, V, P, A>
+{
+    type Item = (&'a K, &'a mut V);
+
+    const NEEDS_RELEASE: bool = false;
+
+    #[inline]
+    fn full_mask(&self, group_index: usize) -> u16 {
+        unsafe { self.storage.as_ref() }.full_mask(group_index)
+    }
+
+    #[inline]
+    unsafe fn take(&self, slot: usize) -> Self::Item {
+        let (key, value) = unsafe { &mut *self.storage.as_ref().pair_ptr(slot).as_ptr() };
+        (key, value)
+    }
+}
+
+/// 移出 `(K, V)`，结束后由 [`ForgetAll`] 把分组表标记为空
+struct PairMoves<'a, K, V, P: StoragePolicy, A: Allocator + Clone> {
+    storage: NonNull<Storage<K, V, P, A>>,
+    marker: PhantomData<&'a mut (K, V)>,
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Clone for PairMoves<'_, K, V, P, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Copy for PairMoves<'_, K, V, P, A> {}
+
+// 键值对被移到其他线程；节点策略在各线程中通过共享的分配器释放节点
+unsafe impl<K: Send, V: Send, P: StoragePolicy, A: Allocator + Clone + Sync> Send for PairMoves<'_, K, V, P, A> {}
+unsafe impl<K: Send, V: Send, P: StoragePolicy, A: Allocator + Clone + Sync> Sync for PairMoves<'_, K, V, P, A> {}
+
+impl<K: Send, V: Send, P: StoragePolicy, A: Allocator + Clone + Sync> SlotSource for PairMoves<'_, K, V, P, A> {
+    type Item = (K, V);
+
+    // 节点策略的节点内存只能通过取出释放
+    const NEEDS_RELEASE: bool = true;
+
+    #[inline]
+    fn full_mask(&self, group_index: usize) -> u16 {
+        unsafe { self.storage.as_ref() }.full_mask(group_index)
+    }
+
+    #[inline]
+    unsafe fn take(&self, slot: usize) -> Self::Item {
+        unsafe { self.storage.as_ref().read_pair(slot) }
+    }
+}
+
+/// 并行移出结束时（包括 panic）清空分组表但不析构元素：
+/// 已产出的元素归消费者所有，其余的已由生产者析构或泄漏
+struct ForgetAll<K, V, P: StoragePolicy, A: Allocator + Clone>(NonNull<Storage<K, V, P, A>>);
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Drop for ForgetAll<K, V, P, A> {
+    fn drop(&mut self) {
+        unsafe { self.0.as_mut() }.forget_all();
+    }
+}
+
+/// 并行不可变迭代器，由 [`F14Map::par_iter`] 创建
+pub struct ParIter<'a, K, V, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    source: PairRefs<'a, K, V, P, A>,
+    group_count: usize,
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Clone for ParIter<'_, K, V, P, A> {
+    fn clone(&self) -> Self {
+        Self { source: self.source, group_count: self.group_count }
+    }
+}
+
+impl<'a, K, V, P, A> ParallelIterator for ParIter<'a, K, V, P, A>
+where
+    K: Sync + 'a,
+    V: Sync + 'a,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    type Item = (&'a K, &'a V);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge_unindexed(GroupProducer::new(self.source, self.group_count), consumer)
+    }
+}
+
+/// 并行可变迭代器，由 [`F14Map::par_iter_mut`] 创建
+///
+/// 只能修改值：键决定了元素在分组表中的位置。
+pub struct ParIterMut<'a, K, V, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    source: PairMuts<'a, K, V, P, A>,
+    group_count: usize,
+}
+
+impl<'a, K, V, P, A> ParallelIterator for ParIterMut<'a, K, V, P, A>
+where
+    K: Sync + 'a,
+    V: Send + 'a,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    type Item = (&'a K, &'a mut V);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge_unindexed(GroupProducer::new(self.source, self.group_count), consumer)
+    }
+}
+
+/// 并行移出所有键值对的迭代器，由 [`F14Map::par_drain`] 创建
+///
+/// 迭代结束后映射为空但保留内存；未被驱动就析构时直接清空映射。
+pub struct ParDrain<'a, K, V, P = VectorPolicy, A = AlignedAllocator>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    source: PairMoves<'a, K, V, P, A>,
+    group_count: usize,
+}
+
+impl<K, V, P, A> ParallelIterator for ParDrain<'_, K, V, P, A>
+where
+    K: Send,
+    V: Send,
+    P: StoragePolicy,
+    A: Allocator + Clone + Sync,
+{
+    type Item = (K, V);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        // 守卫先于 `self` 析构，`ParDrain` 的 Drop 随后看到的是空表
+        let _guard: ForgetAll<K, V, P, A> = ForgetAll(self.source.storage);
+        bridge_unindexed(GroupProducer::new(self.source, self.group_count), consumer)
+    }
+}
+
+impl<K, V, P: StoragePolicy, A: Allocator + Clone> Drop for ParDrain<'_, K, V, P, A> {
+    fn drop(&mut self) {
+        unsafe { self.source.storage.as_mut() }.clear();
+    }
+}
+
+impl<K, V, S, P, A> F14Map<K, V, S, P, A>
+where
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 并行遍历键值对，按分组区间拆分给工作线程，产出顺序不确定
+    pub fn par_iter(&self) -> ParIter<'_, K, V, P, A>
+    where
+        K: Sync,
+        V: Sync,
+    {
+        ParIter {
+            source: PairRefs { storage: NonNull::from(self.storage()), marker: PhantomData },
+            group_count: self.group_count(),
+        }
+    }
+
+    /// 并行遍历键值对，可修改值
+    pub fn par_iter_mut(&mut self) -> ParIterMut<'_, K, V, P, A>
+    where
+        K: Sync,
+        V: Send,
+    {
+        let group_count = self.group_count();
+        ParIterMut {
+            source: PairMuts { storage: NonNull::from(self.storage_mut()), marker: PhantomData },
+            group_count,
+        }
+    }
+
+    /// 并行移出所有键值对，保留已分配的内存
+    pub fn par_drain(&mut self) -> ParDrain<'_, K, V, P, A>
+    where
+        K: Send,
+        V: Send,
+        A: Sync,
+    {
+        let group_count = self.group_count();
+        ParDrain {
+            source: PairMoves { storage: NonNull::from(self.storage_mut()), marker: PhantomData },
+            group_count,
+        }
+    }
+}
+
+impl<K, V, S, P, A> F14Map<K, V, S, P, A>
+where
+    K: Eq + Hash,
+    S: BuildHasherExt,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// 并行插入所有键值对，已存在的键被覆盖
+    ///
+    /// 分两阶段：先并行计算哈希，再按总数一次预留空间（映射非空时只预留一半），
+    /// 用预先计算的哈希顺序插入。同一个键出现多次时保留哪一个不确定。
+    /// 出错时已插入的键值对保留在映射中。
+    pub fn try_par_extend<I>(&mut self, par_iter: I) -> Result<(), MapError>
+    where
+        K: Send,
+        V: Send,
+        S: Sync,
+        I: IntoParallelIterator<Item = (K, V)>,
+    {
+        let hasher_builder = self.hasher();
+        let hashed: LinkedList<Vec<(u64, K, V)>> = par_iter
+            .into_par_iter()
+            .map(|(key, value)| (make_hash(hasher_builder, &key).0, key, value))
+            .collect_vec_list();
+
+        let total: usize = hashed.iter().map(Vec::len).sum();
+        self.try_reserve(if self.is_empty() { total } else { total.div_ceil(2) })?;
+        for (hash, key, value) in hashed.into_iter().flatten() {
+            self.insert_with_hash(hash, key, value)?;
+        }
+        Ok(())
+    }
+
+    /// 只保留 `keep` 返回 `true` 的键值对，谓词在各分组上并行执行
+    ///
+    /// 先并行求出每个分组要删除的槽位掩码，再顺序删除；删除的槽位直接恢复为 EMPTY，不留墓碑。
+    pub fn par_retain<F>(&mut self, keep: F)
+    where
+        K: Sync,
+        V: Send,
+        F: Fn(&K, &mut V) -> bool + Sync,
+    {
+        let group_count = self.group_count();
+        let source: PairMuts<'_, K, V, P, A> =
+            PairMuts { storage: NonNull::from(self.storage_mut()), marker: PhantomData };
+        let removed: Vec<u16> = (0..group_count)
+            .into_par_iter()
+            .map(|group_index| {
+                let mut mask = source.full_mask(group_index);
+                let mut removed = 0u16;
+                while mask != 0 {
+                    let bit = mask.trailing_zeros();
+                    mask &= mask - 1;
+                    let (key, value) = unsafe { source.take(group_index * CHUNK_SIZE + bit as usize) };
+                    if !keep(key, value) {
+                        removed |= 1 << bit;
+                    }
+                }
+                removed
+            })
+            .collect();
+
+        for (group_index, mut mask) in removed.into_iter().enumerate() {
+            while mask != 0 {
+                let slot = group_index * CHUNK_SIZE + mask.trailing_zeros() as usize;
+                mask &= mask - 1;
+                drop(self.take_slot(slot));
+            }
+        }
+    }
+}
+
+impl<K, V, S, P, A> ParallelExtend<(K, V)> for F14Map<K, V, S, P, A>
+where
+    K: Eq + Hash + Send,
+    V: Send,
+    S: BuildHasherExt + Sync,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    /// # Panics
+    /// 容量溢出或分配失败时 panic，需要处理错误时使用 [`F14Map::try_par_extend`]。
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = (K, V)>,
+    {
+        if let Err(err) = self.try_par_extend(par_iter) {
+            panic!("F14Map::par_extend failed: {}", err);
+        }
+    }
+}
+
+impl<'a, K, V, S, P, A> IntoParallelIterator for &'a F14Map<K, V, S, P, A>
+where
+    K: Sync,
+    V: Sync,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    type Item = (&'a K, &'a V);
+    type Iter = ParIter<'a, K, V, P, A>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.par_iter()
+    }
+}
+
+impl<'a, K, V, S, P, A> IntoParallelIterator for &'a mut F14Map<K, V, S, P, A>
+where
+    K: Sync,
+    V: Send,
+    P: StoragePolicy,
+    A: Allocator + Clone,
+{
+    type Item = (&'a K, &'a mut V);
+    type Iter = ParIterMut<'a, K, V, P, A>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.par_iter_mut()
+    }
+}
@@ -0,0 +1,57 @@
+//! 存储策略
+//!
+//! [`F14Map`](crate::F14Map) 通过策略类型参数选择键值对的存放方式，
+//! 控制字节、溢出元数据与探测代码在所有策略之间共用：
+//!
+//! | 策略 | 槽位内容 | 适用场景 |
+//! |------|----------|----------|
+//! | [`ValuePolicy`] | `(K, V)` | 小而简单的键值对，查找少一次间接访问 |
+//! | [`NodePolicy`] | 指向 `(K, V)` 的指针 | 大结构体，扩容只搬移指针，引用地址稳定 |
+//! | [`VectorPolicy`] | `u32` 下标 | 连续存储，遍历即切片扫描 |
+//! | [`FastPolicy`] | 按大小选择 | 键值对小于 24 字节用值布局，否则用向量布局 |
+
+use crate::allocator::Allocator;
+use crate::storage::{FastStorage, Node, RawStorage, Sealed, TableStorage, VectorStorage};
+
+/// 存储策略，由本 crate 提供的策略类型实现
+pub trait StoragePolicy: Sealed + 'static {
+    #[doc(hidden)]
+    type Storage<K, V, A: Allocator + Clone>: RawStorage<K, V, A>;
+}
+
+/// 值策略：键值对直接存放在分组槽位中（对应 Folly F14Value）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValuePolicy;
+
+/// 节点策略：槽位中存放指向键值对的指针（对应 Folly F14Node）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NodePolicy;
+
+/// 向量策略：键值对连续存放，槽位中存放 32 位下标（对应 Folly F14Vector）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VectorPolicy;
+
+/// 快速策略：按键值对大小在值策略与向量策略之间选择（对应 Folly F14Fast）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastPolicy;
+
+impl Sealed for ValuePolicy {}
+impl Sealed for NodePolicy {}
+impl Sealed for VectorPolicy {}
+impl Sealed for FastPolicy {}
+
+impl StoragePolicy for ValuePolicy {
+    type Storage<K, V, A: Allocator + Clone> = TableStorage<K, V, (K, V), A>;
+}
+
+impl StoragePolicy for NodePolicy {
+    type Storage<K, V, A: Allocator + Clone> = TableStorage<K, V, Node<K, V>, A>;
+}
+
+impl StoragePolicy for VectorPolicy {
+    type Storage<K, V, A: Allocator + Clone> = VectorStorage<K, V, A>;
+}
+
+impl StoragePolicy for FastPolicy {
+    type Storage<K, V, A: Allocator + Clone> = FastStorage<K, V, A>;
+}
@@ -26,9 +26,13 @@ impl HybridProbeStrategy {
             global_probe_count: 0,
         }
     }
-    
+}
+
+impl Iterator for HybridProbeStrategy {
+    type Item = usize;
+
     /// 获取下一个探测位置
-    pub fn next(&mut self) -> Option<usize> {
+    fn next(&mut self) -> Option<usize> {
         if self.global_probe_count >= self.group_count * 2 {
             return None;
         }
@@ -49,4 +53,45 @@ impl HybridProbeStrategy {
         self.group_probe_count = 0;
         self.next()  // 递归调用处理新组
     }
-}
\ No newline at end of file
+}
+
+/// 分组探测序列 - 从起始组出发，按奇数步长在组间跳转
+///
+/// 与 Folly F14 一致：分组数为 2 的幂，步长 `2 * fragment + 1` 为奇数，
+/// 因此 `group_count` 次跳转恰好覆盖每个分组一次。依次产出分组索引，
+/// 首个分组为哈希定位的起始组。
+pub struct GroupProbeSeq {
+    group_index: usize,   // 当前组索引
+    group_mask: usize,    // 分组数 - 1
+    step: usize,          // 组间步长（奇数）
+    remaining: usize,     // 剩余可探测组数
+}
+
+impl GroupProbeSeq {
+    pub fn new(full_hash: u64, fragment: u8, group_count: usize) -> Self {
+        debug_assert!(group_count == 0 || group_count.is_power_of_two());
+        let group_mask = group_count.wrapping_sub(1);
+
+        Self {
+            group_index: full_hash as usize & group_mask,
+            group_mask,
+            step: 2 * fragment as usize + 1,
+            remaining: group_count,
+        }
+    }
+}
+
+impl Iterator for GroupProbeSeq {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let group_index = self.group_index;
+        self.group_index = (self.group_index + self.step) & self.group_mask;
+        Some(group_index)
+    }
+}
@@ -0,0 +1,843 @@
+//! F14 分组索引表
+//!
+//! 控制字节、分组溢出元数据与槽位数组放在同一块对齐内存中，
+//! 负责 SIMD 组内匹配、溢出计数维护与探测；槽位中存放什么由上层决定。
+
+use crate::dispatch_simd;
+use super::{
+    simd_utils::{self, ChunkMeta, CHUNK_SIZE, EMPTY, DELETED, FULL_MASK},
+    error::MapError,
+    allocator::{self, Allocator},
+    probe_strategy::GroupProbeSeq,
+};
+use std::{
+    iter::FusedIterator, marker::PhantomData, mem, ptr::{self, NonNull}
+};
+
+pub(crate) const MAX_CAPACITY: usize = usize::MAX / (CHUNK_SIZE * 2);
+
+/// 容纳 `len` 个元素且插入时不触发扩容（负载因子 7/10）所需的表容量
+///
+/// 返回值已按分组数为 2 的幂取整；容量溢出时返回 `None`。
+pub(crate) fn capacity_for(len: usize) -> Option<usize> {
+    if len == 0 {
+        return Some(0);
+    }
+    let slots = len.checked_mul(10)?.div_ceil(7);
+    let capacity = slots.div_ceil(CHUNK_SIZE).checked_next_power_of_two()? * CHUNK_SIZE;
+    (capacity <= MAX_CAPACITY).then_some(capacity)
+}
+
+/// 容量为 `capacity` 的表在负载因子 7/10 下最多容纳的元素数量
+#[inline]
+pub(crate) fn max_len(capacity: usize) -> usize {
+    capacity * 7 / 10
+}
+
+/// 槽位状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlotState {
+    Empty,
+    Deleted,
+    Full,
+}
+
+/// 表内存布局：`[控制字节 | 分组元数据 | 填充 | 槽位]`
+struct TableLayout {
+    meta_offset: usize,
+    slots_offset: usize,
+    total_size: usize,
+}
+
+/// 分组索引表，内存通过分配器 `A` 分配
+pub(crate) struct RawTable<T, A: Allocator> {
+    // 控制字节数组
+    ctrls: NonNull<u8>,
+    // 分组溢出元数据数组（每组一个）
+    meta: NonNull<ChunkMeta>,
+    // 槽位数组
+    slots: NonNull<T>,
+    // 容量（总槽位数）
+    capacity: usize,
+    // 分组数
+    group_count: usize,
+    // 有效元素数量
+    len: usize,
+    // 删除标记数量
+    deleted: usize,
+    // 分配器
+    alloc: A,
+    // 标记类型关系
+    phantom: PhantomData<T>,
+}
+
+impl<T, A: Allocator> RawTable<T, A> {
+    /// 创建不分配内存的空表
+    pub(crate) fn new_in(alloc: A) -> Self {
+        Self {
+            ctrls: NonNull::dangling(),
+            meta: NonNull::dangling(),
+            slots: NonNull::dangling(),
+            capacity: 0,
+            group_count: 0,
+            len: 0,
+            deleted: 0,
+            alloc,
+            phantom: PhantomData,
+        }
+    }
+
+    /// 创建至少能容纳 `capacity` 个槽位的表
+    ///
+    /// 容量向上取整为 `CHUNK_SIZE` 的倍数，且分组数为 2 的幂（探测序列需要覆盖所有分组）。
+    pub(crate) fn with_capacity_in(capacity: usize, alloc: A) -> Result<Self, MapError> {
+        // 检查容量是否过大（在分配内存前）
+        if capacity > MAX_CAPACITY {
+            return Err(MapError::CapacityExceeded);
+        }
+        if capacity == 0 {
+            return Ok(Self::new_in(alloc));
+        }
+
+        let group_count = capacity.div_ceil(CHUNK_SIZE).next_power_of_two();
+        let capacity = group_count * CHUNK_SIZE;
+        if capacity > MAX_CAPACITY {
+            return Err(MapError::CapacityExceeded);
+        }
+
+        // 计算布局并分配内存
+        let layout = Self::calculate_layout(capacity)?;
+        let ptr = allocator::alloc_simd_in(&alloc, layout.total_size)?;
+
+        // 初始化控制字节为EMPTY
+        dispatch_simd!(
+            fill_ctrls,
+            ptr.as_ptr(),
+            EMPTY,
+            capacity
+        );
+
+        // 初始化分组元数据（无溢出）
+        let meta_ptr = unsafe { ptr.as_ptr().add(layout.meta_offset) } as *mut ChunkMeta;
+        unsafe { ptr::write_bytes(meta_ptr, 0, group_count) };
+
+        // 槽位数组在元数据之后
+        let slots_ptr = unsafe { ptr.as_ptr().add(layout.slots_offset) } as *mut T;
+
+        Ok(Self {
+            ctrls: ptr,
+            meta: NonNull::new(meta_ptr).ok_or(MapError::CapacityExceeded)?,
+            slots: NonNull::new(slots_ptr).ok_or(MapError::CapacityExceeded)?,
+            capacity,
+            group_count,
+            len: 0,
+            deleted: 0,
+            alloc,
+            phantom: PhantomData,
+        })
+    }
+
+    /// 计算内存布局
+    fn calculate_layout(capacity: usize) -> Result<TableLayout, MapError> {
+        // 控制字节之后紧跟分组元数据
+        let meta_offset = capacity * mem::size_of::<u8>();
+        let meta_size = (capacity / CHUNK_SIZE) * mem::size_of::<ChunkMeta>();
+
+        // 槽位按自身对齐要求放在元数据之后
+        let slot_align = mem::align_of::<T>();
+        if slot_align > simd_utils::SIMD_ALIGNMENT {
+            return Err(MapError::CapacityExceeded);
+        }
+        let slots_offset = (meta_offset + meta_size).next_multiple_of(slot_align);
+        let slots_size = capacity
+            .checked_mul(mem::size_of::<T>())
+            .ok_or(MapError::CapacityExceeded)?;
+
+        // 总大小
+        let total_size = slots_offset
+            .checked_add(slots_size)
+            .ok_or(MapError::CapacityExceeded)?;
+
+        Ok(TableLayout { meta_offset, slots_offset, total_size })
+    }
+
+    /// 获取分配器
+    #[inline]
+    pub(crate) fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// 获取容量
+    #[inline]
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 获取分组数量
+    #[inline]
+    pub(crate) fn group_count(&self) -> usize {
+        self.group_count
+    }
+
+    /// 获取元素数量
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// 获取删除标记数量
+    #[inline]
+    pub(crate) fn deleted(&self) -> usize {
+        self.deleted
+    }
+
+    /// 是否需要扩容才能再插入一个元素（负载因子 7/10）
+    #[inline]
+    pub(crate) fn needs_grow(&self) -> bool {
+        self.capacity == 0 || self.len >= max_len(self.capacity)
+    }
+
+    /// 获取指定索引的控制字节
+    #[inline]
+    pub(crate) fn ctrl(&self, index: usize) -> u8 {
+        debug_assert!(index < self.capacity);
+        unsafe { *self.ctrls.as_ptr().add(index) }
+    }
+
+    /// 设置指定索引的控制字节
+    #[inline]
+    fn set_ctrl(&mut self, index: usize, ctrl: u8) {
+        debug_assert!(index < self.capacity);
+        unsafe { *self.ctrls.as_ptr().add(index) = ctrl }
+    }
+
+    /// 获取槽位状态
+    pub(crate) fn slot_state(&self, index: usize) -> SlotState {
+        let ctrl = self.ctrl(index);
+        match ctrl {
+            EMPTY => SlotState::Empty,
+            DELETED => SlotState::Deleted,
+            _ if ctrl & FULL_MASK == ctrl => SlotState::Full,
+            _ => SlotState::Deleted, // 无效状态处理为Deleted
+        }
+    }
+
+    /// 获取指定分组的溢出元数据
+    #[inline]
+    pub(crate) fn chunk_meta(&self, group_index: usize) -> ChunkMeta {
+        assert!(group_index < self.group_count, "group index out of range");
+        unsafe { *self.meta.as_ptr().add(group_index) }
+    }
+
+    /// 获取指定分组的溢出元数据可变引用
+    #[inline]
+    fn chunk_meta_mut(&mut self, group_index: usize) -> &mut ChunkMeta {
+        debug_assert!(group_index < self.group_count);
+        unsafe { &mut *self.meta.as_ptr().add(group_index) }
+    }
+
+    /// 获取槽位引用
+    ///
+    /// # Safety
+    /// `index` 处的槽位必须处于 FULL 状态。
+    #[inline]
+    pub(crate) unsafe fn slot(&self, index: usize) -> &T {
+        debug_assert!(index < self.capacity);
+        unsafe { &*self.slots.as_ptr().add(index) }
+    }
+
+    /// 获取槽位可变引用
+    ///
+    /// # Safety
+    /// `index` 处的槽位必须处于 FULL 状态。
+    #[inline]
+    pub(crate) unsafe fn slot_mut(&mut self, index: usize) -> &mut T {
+        debug_assert!(index < self.capacity);
+        unsafe { &mut *self.slots.as_ptr().add(index) }
+    }
+
+    /// 槽位指针，供并行迭代在互不相交的槽位上同时访问
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub(crate) fn slot_ptr(&self, index: usize) -> NonNull<T> {
+        debug_assert!(index < self.capacity);
+        unsafe { self.slots.add(index) }
+    }
+
+    /// 哈希对应的起始组
+    #[inline]
+    fn home_group(&self, full_hash: u64) -> usize {
+        full_hash as usize & (self.group_count - 1)
+    }
+
+    /// 在组内查找片段匹配且满足 `eq` 的槽位
+    #[inline]
+    fn find_in_group(
+        &self,
+        group_start: usize,
+        fragment: u8,
+        eq: &mut impl FnMut(&T) -> bool,
+    ) -> Option<usize> {
+        // 使用 SIMD 查找所有匹配片段的位置
+        let matches = unsafe {
+            simd_utils::find_all_matches(self.ctrls.as_ptr().add(group_start), fragment)
+        };
+        // 检查所有匹配位置
+        for &slot_in_group in matches.iter() {
+            // 遇到 0xFF 表示结束
+            if slot_in_group == 0xFF {
+                break;
+            }
+
+            let index = group_start + slot_in_group as usize;
+            if eq(unsafe { self.slot(index) }) {
+                return Some(index);
+            }
+        }
+        None
+    }
+
+    /// 在组内查找空闲位置
+    #[inline]
+    fn find_empty_in_group(&self, group_start: usize) -> Option<usize> {
+        unsafe { simd_utils::simd_find_empty(self.ctrls.as_ptr().add(group_start)) }
+            .map(|slot| group_start + slot)
+    }
+
+    /// 分组中 FULL 槽位的位掩码
+    #[inline]
+    pub(crate) fn full_mask(&self, group_index: usize) -> u16 {
+        debug_assert!(group_index < self.group_count);
+        unsafe { simd_utils::simd_match_full(self.ctrls.as_ptr().add(group_index * CHUNK_SIZE)) }
+    }
+
+    /// 沿探测序列查找满足 `eq` 的槽位索引
+    ///
+    /// 未命中的分组若没有越界溢出（`outbound == 0`），说明没有键越过该组，
+    /// 查找立即终止，未命中查询的期望探测组数为 O(1)。
+    pub(crate) fn find(
+        &self,
+        full_hash: u64,
+        fragment: u8,
+        mut eq: impl FnMut(&T) -> bool,
+    ) -> Option<usize> {
+        for group_index in GroupProbeSeq::new(full_hash, fragment, self.group_count) {
+            let group_start = group_index * CHUNK_SIZE;
+            if let Some(index) = self.find_in_group(group_start, fragment, &mut eq) {
+                return Some(index);
+            }
+            if self.chunk_meta(group_index).outbound_overflow() == 0 {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// 预取起始分组的控制字节和槽位，供随后的 [`find`](Self::find) 使用
+    #[inline]
+    pub(crate) fn prefetch(&self, full_hash: u64, fragment: u8) {
+        if let Some(group_index) = GroupProbeSeq::new(full_hash, fragment, self.group_count).next() {
+            let group_start = group_index * CHUNK_SIZE;
+            simd_utils::prefetch_read(unsafe { self.ctrls.as_ptr().add(group_start) });
+            simd_utils::prefetch_read(unsafe { self.slots.as_ptr().add(group_start) });
+        }
+    }
+
+    /// 沿探测序列查找首个空闲槽位（不修改溢出计数）
+    pub(crate) fn find_insert_slot(&self, full_hash: u64, fragment: u8) -> Option<usize> {
+        GroupProbeSeq::new(full_hash, fragment, self.group_count)
+            .find_map(|group_index| self.find_empty_in_group(group_index * CHUNK_SIZE))
+    }
+
+    /// 在空闲槽位 `index` 写入元素，并记录探测途经分组的溢出
+    pub(crate) fn insert_at(&mut self, index: usize, full_hash: u64, fragment: u8, value: T) {
+        // 更新计数
+        match self.slot_state(index) {
+            SlotState::Full => panic!("Inserting into a full slot"),
+            SlotState::Deleted => self.deleted -= 1,
+            SlotState::Empty => {}
+        }
+
+        unsafe { ptr::write(self.slots.as_ptr().add(index), value) };
+        self.set_ctrl(index, fragment);
+        self.record_overflow(full_hash, fragment, index);
+        self.len += 1;
+    }
+
+    /// 移出 `index` 处的元素，槽位直接恢复为 EMPTY
+    ///
+    /// 溢出计数保证了查找的正确性，空槽位本身不会终止探测，因此删除无需留下墓碑，
+    /// 只需撤销插入时沿探测序列记录的溢出。
+    pub(crate) fn erase_at(&mut self, index: usize, full_hash: u64, fragment: u8) -> T {
+        debug_assert_eq!(self.slot_state(index), SlotState::Full);
+        self.set_ctrl(index, EMPTY);
+        self.erase_overflow(full_hash, fragment, index);
+        self.len -= 1;
+        unsafe { ptr::read(self.slots.as_ptr().add(index)) }
+    }
+
+    /// 移出 `index` 处的元素并留下删除标记
+    ///
+    /// 不知道元素哈希时使用：溢出计数保持不变，墓碑由 `rebuild` 统一清理。
+    pub(crate) fn tombstone_at(&mut self, index: usize) -> T {
+        debug_assert_eq!(self.slot_state(index), SlotState::Full);
+        self.set_ctrl(index, DELETED);
+        self.deleted += 1;
+        self.len -= 1;
+        unsafe { ptr::read(self.slots.as_ptr().add(index)) }
+    }
+
+    /// 记录插入到 `index` 的元素的溢出：途经的分组越界计数加一，目标组托管计数加一
+    fn record_overflow(&mut self, full_hash: u64, fragment: u8, index: usize) {
+        let target_group = index / CHUNK_SIZE;
+        for group_index in GroupProbeSeq::new(full_hash, fragment, self.group_count) {
+            if group_index == target_group {
+                break;
+            }
+            self.chunk_meta_mut(group_index).inc_outbound();
+        }
+        if target_group != self.home_group(full_hash) {
+            self.chunk_meta_mut(target_group).inc_hosted();
+        }
+    }
+
+    /// 撤销 [`Self::record_overflow`] 记录的溢出（删除位于 `index` 的元素时调用）
+    fn erase_overflow(&mut self, full_hash: u64, fragment: u8, index: usize) {
+        let target_group = index / CHUNK_SIZE;
+        for group_index in GroupProbeSeq::new(full_hash, fragment, self.group_count) {
+            if group_index == target_group {
+                break;
+            }
+            self.chunk_meta_mut(group_index).dec_outbound();
+        }
+        if target_group != self.home_group(full_hash) {
+            self.chunk_meta_mut(target_group).dec_hosted();
+        }
+    }
+
+    /// 在原有内存中重新放置所有元素：清除墓碑、重建溢出元数据，不分配内存
+    ///
+    /// 先把 FULL 槽位标记为待放置（借用 DELETED 控制字节）、墓碑改为 EMPTY 并清零
+    /// 溢出计数，再按槽位顺序逐个放置：元素沿探测序列进入第一个有 EMPTY 或待放置
+    /// 槽位的分组；到达自身所在分组时原地不动；目标是待放置槽位时两者交换，
+    /// 换回来的元素在当前位置继续放置。
+    pub(crate) fn rehash_in_place(&mut self, hash: impl Fn(&T) -> (u64, u8)) {
+        if self.capacity == 0 {
+            return;
+        }
+        for index in 0..self.capacity {
+            let ctrl = if self.slot_state(index) == SlotState::Full { DELETED } else { EMPTY };
+            self.set_ctrl(index, ctrl);
+        }
+        unsafe { ptr::write_bytes(self.meta.as_ptr(), 0, self.group_count) };
+        self.deleted = 0;
+
+        let guard = PendingOnUnwind(self);
+        for index in 0..guard.0.capacity {
+            while guard.0.ctrl(index) == DELETED {
+                let (full_hash, fragment) = hash(unsafe { guard.0.slot(index) });
+                guard.0.place_pending(index, full_hash, fragment);
+            }
+        }
+        mem::forget(guard);
+    }
+
+    /// 放置 `index` 处待放置的元素（见 [`Self::rehash_in_place`]）
+    fn place_pending(&mut self, index: usize, full_hash: u64, fragment: u8) {
+        let own_group = index / CHUNK_SIZE;
+        for group_index in GroupProbeSeq::new(full_hash, fragment, self.group_count) {
+            if group_index == own_group {
+                self.set_ctrl(index, fragment);
+                self.record_overflow(full_hash, fragment, index);
+                return;
+            }
+            let group = group_index * CHUNK_SIZE..(group_index + 1) * CHUNK_SIZE;
+            let slots = self.slots.as_ptr();
+            let target = if let Some(target) = group.clone().find(|&slot| self.ctrl(slot) == EMPTY) {
+                unsafe { ptr::copy_nonoverlapping(slots.add(index), slots.add(target), 1) };
+                self.set_ctrl(index, EMPTY);
+                target
+            } else if let Some(target) = group.clone().find(|&slot| self.ctrl(slot) == DELETED) {
+                // `index` 保持待放置状态，由调用方继续放置换回来的元素
+                unsafe { ptr::swap_nonoverlapping(slots.add(index), slots.add(target), 1) };
+                target
+            } else {
+                continue;
+            };
+            self.set_ctrl(target, fragment);
+            self.record_overflow(full_hash, fragment, target);
+            return;
+        }
+        unreachable!("probe sequence visits every group")
+    }
+
+    /// 按槽位顺序遍历 FULL 槽位
+    pub(crate) fn raw_iter(&self) -> RawIter<T> {
+        let (front_mask, back_group, back_mask) = if self.capacity == 0 {
+            (0, 0, 0)
+        } else {
+            let back_group = self.capacity - CHUNK_SIZE;
+            (self.full_mask(0), back_group, self.full_mask(self.group_count - 1))
+        };
+        RawIter {
+            ctrls: self.ctrls,
+            slots: self.slots,
+            front_group: 0,
+            front_mask,
+            back_group,
+            back_mask,
+            remaining: self.len,
+        }
+    }
+
+    /// 复制控制字节与溢出元数据，再逐个克隆 FULL 槽位，不重新计算哈希
+    ///
+    /// `target` 必须是与本表容量相同的空表。`clone` 失败或 panic 时，尚未克隆的槽位
+    /// 恢复为 EMPTY，已克隆的元素留在 `target` 中，由其所有者照常释放。
+    pub(crate) fn clone_into_with(
+        &self,
+        target: &mut Self,
+        mut clone: impl FnMut(&T) -> Result<T, MapError>,
+    ) -> Result<(), MapError> {
+        debug_assert!(target.capacity == self.capacity && target.len == 0);
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(self.ctrls.as_ptr(), target.ctrls.as_ptr(), self.capacity);
+            ptr::copy_nonoverlapping(self.meta.as_ptr(), target.meta.as_ptr(), self.group_count);
+        }
+        target.deleted = self.deleted;
+
+        let mut guard = UnclonedOnFailure { table: target, next: 0 };
+        for group_index in 0..self.group_count {
+            let group_start = group_index * CHUNK_SIZE;
+            let mut mask = self.full_mask(group_index);
+            while mask != 0 {
+                let index = group_start + mask.trailing_zeros() as usize;
+                mask &= mask - 1;
+                guard.next = index;
+                let value = clone(unsafe { self.slot(index) })?;
+                unsafe { ptr::write(guard.table.slots.as_ptr().add(index), value) };
+                guard.table.len += 1;
+            }
+        }
+        guard.next = self.capacity;
+        Ok(())
+    }
+
+    /// 移出所有元素，迭代器析构后表为空但保留内存
+    pub(crate) fn drain(&mut self) -> RawDrain<'_, T, A>
+    where
+        A: Clone,
+    {
+        // 迭代期间原表替换为不分配内存的空表，即使迭代器被遗忘也保持一致
+        let table = mem::replace(self, Self::new_in(self.alloc.clone()));
+        RawDrain { iter: RawIntoIter::new(table), orig: self }
+    }
+
+    /// 清空所有槽位（不析构元素）
+    pub(crate) fn clear_no_drop(&mut self) {
+        if self.capacity == 0 {
+            return;
+        }
+        unsafe {
+            dispatch_simd!(
+                fill_ctrls,
+                self.ctrls.as_ptr(),
+                EMPTY,
+                self.capacity
+            );
+            // 清空后不再有溢出
+            ptr::write_bytes(self.meta.as_ptr(), 0, self.group_count);
+        }
+        self.len = 0;
+        self.deleted = 0;
+    }
+
+    /// 析构所有元素并清空
+    pub(crate) fn clear(&mut self) {
+        if mem::needs_drop::<T>() {
+            for slot in self.raw_iter() {
+                unsafe { ptr::drop_in_place(slot.as_ptr()) };
+            }
+        }
+        self.clear_no_drop();
+    }
+}
+
+// 表独占其内存，线程安全性与槽位数据和分配器一致
+unsafe impl<T: Send, A: Allocator + Send> Send for RawTable<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for RawTable<T, A> {}
+
+impl<T, A: Allocator> Drop for RawTable<T, A> {
+    fn drop(&mut self) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        // 释放所有元素
+        self.clear();
+
+        // 释放内存
+        let layout = Self::calculate_layout(self.capacity)
+            .expect("Invalid layout calculation");
+        unsafe { allocator::dealloc_simd_in(&self.alloc, self.ctrls, layout.total_size) };
+    }
+}
+
+/// 克隆中途失败时，把 `next` 及之后复制过来的 FULL 控制字节恢复为 EMPTY
+struct UnclonedOnFailure<'a, T, A: Allocator> {
+    table: &'a mut RawTable<T, A>,
+    // 第一个尚未写入的槽位
+    next: usize,
+}
+
+impl<T, A: Allocator> Drop for UnclonedOnFailure<'_, T, A> {
+    fn drop(&mut self) {
+        for index in self.next..self.table.capacity {
+            if self.table.slot_state(index) == SlotState::Full {
+                self.table.set_ctrl(index, EMPTY);
+            }
+        }
+    }
+}
+
+/// 原地重新放置过程中哈希函数 panic 时，把剩余待放置的元素保留为 FULL
+///
+/// 这些元素的片段无法再计算，只能以片段 0 保留下来，并把所有分组的越界计数置为饱和：
+/// 表仍然内存安全、元素会被正常析构，但它们可能无法再通过键查到。
+struct PendingOnUnwind<'a, T, A: Allocator>(&'a mut RawTable<T, A>);
+
+impl<T, A: Allocator> Drop for PendingOnUnwind<'_, T, A> {
+    fn drop(&mut self) {
+        let table = &mut *self.0;
+        for index in 0..table.capacity {
+            if table.ctrl(index) == DELETED {
+                table.set_ctrl(index, 0);
+            }
+        }
+        for group_index in 0..table.group_count {
+            table.chunk_meta_mut(group_index).saturate_outbound();
+        }
+    }
+}
+
+/// 遍历 FULL 槽位的原始迭代器
+///
+/// 每次取一个分组的 FULL 位掩码，正向按最低位、反向按最高位依次产出，
+/// 稀疏的表不必逐个检查控制字节。
+/// 只保存指针，不借用表；由上层迭代器绑定生命周期并保证表在迭代期间不被修改。
+pub(crate) struct RawIter<T> {
+    ctrls: NonNull<u8>,
+    slots: NonNull<T>,
+    // 正向当前分组的起始槽位及其中尚未产出的 FULL 槽位
+    front_group: usize,
+    front_mask: u16,
+    // 反向当前分组的起始槽位及其中尚未产出的 FULL 槽位
+    back_group: usize,
+    back_mask: u16,
+    // 尚未产出的 FULL 槽位数；正反两端共享，保证两端不会越过彼此
+    remaining: usize,
+}
+
+impl<T> RawIter<T> {
+    /// 分组的 FULL 位掩码
+    #[inline]
+    fn full_mask(&self, group_start: usize) -> u16 {
+        unsafe { simd_utils::simd_match_full(self.ctrls.as_ptr().add(group_start)) }
+    }
+
+    /// 槽位指针
+    #[inline]
+    fn slot(&self, index: usize) -> NonNull<T> {
+        unsafe { NonNull::new_unchecked(self.slots.as_ptr().add(index)) }
+    }
+}
+
+impl<T> Clone for RawIter<T> {
+    fn clone(&self) -> Self {
+        Self {
+            ctrls: self.ctrls,
+            slots: self.slots,
+            front_group: self.front_group,
+            front_mask: self.front_mask,
+            back_group: self.back_group,
+            back_mask: self.back_mask,
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<T> Iterator for RawIter<T> {
+    type Item = NonNull<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // remaining > 0 时前方一定还有 FULL 槽位，不会越过表末尾
+        while self.front_mask == 0 {
+            self.front_group += CHUNK_SIZE;
+            self.front_mask = self.full_mask(self.front_group);
+        }
+        let index = self.front_group + self.front_mask.trailing_zeros() as usize;
+        self.front_mask &= self.front_mask - 1;
+        self.remaining -= 1;
+        Some(self.slot(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+
+    /// 逐组取掩码、在局部变量中消耗，避免每个元素都回写迭代器状态
+    #[inline]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let mut acc = init;
+        let mut remaining = self.remaining;
+        let mut group = self.front_group;
+        let mut mask = self.front_mask;
+        loop {
+            // 反向迭代可能已取走当前分组的高位，产出数不超过 remaining
+            while mask != 0 && remaining > 0 {
+                let index = group + mask.trailing_zeros() as usize;
+                mask &= mask - 1;
+                remaining -= 1;
+                acc = f(acc, self.slot(index));
+            }
+            if remaining == 0 {
+                return acc;
+            }
+            group += CHUNK_SIZE;
+            mask = self.full_mask(group);
+        }
+    }
+}
+
+impl<T> DoubleEndedIterator for RawIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        while self.back_mask == 0 {
+            self.back_group -= CHUNK_SIZE;
+            self.back_mask = self.full_mask(self.back_group);
+        }
+        let bit = u16::BITS - 1 - self.back_mask.leading_zeros();
+        self.back_mask &= !(1 << bit);
+        self.remaining -= 1;
+        Some(self.slot(self.back_group + bit as usize))
+    }
+}
+
+impl<T> ExactSizeIterator for RawIter<T> {}
+
+impl<T> FusedIterator for RawIter<T> {}
+
+/// 移出所有元素的消耗迭代器，持有表的所有权
+pub(crate) struct RawIntoIter<T, A: Allocator> {
+    iter: RawIter<T>,
+    table: RawTable<T, A>,
+}
+
+impl<T, A: Allocator> RawIntoIter<T, A> {
+    pub(crate) fn new(table: RawTable<T, A>) -> Self {
+        Self { iter: table.raw_iter(), table }
+    }
+
+    /// 获取表的分配器
+    #[inline]
+    pub(crate) fn allocator(&self) -> &A {
+        self.table.allocator()
+    }
+}
+
+impl<T, A: Allocator> Iterator for RawIntoIter<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|slot| unsafe { ptr::read(slot.as_ptr()) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for RawIntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back().map(|slot| unsafe { ptr::read(slot.as_ptr()) })
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for RawIntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for RawIntoIter<T, A> {}
+
+impl<T, A: Allocator> Drop for RawIntoIter<T, A> {
+    fn drop(&mut self) {
+        // 析构尚未取出的元素，表本身只释放内存
+        for slot in &mut self.iter {
+            unsafe { ptr::drop_in_place(slot.as_ptr()) };
+        }
+        self.table.clear_no_drop();
+    }
+}
+
+/// 移出所有元素的迭代器，析构时把清空的表交还原处
+pub(crate) struct RawDrain<'a, T, A: Allocator> {
+    iter: RawIntoIter<T, A>,
+    orig: &'a mut RawTable<T, A>,
+}
+
+impl<T, A: Allocator> RawDrain<'_, T, A> {
+    /// 获取表的分配器
+    #[inline]
+    pub(crate) fn allocator(&self) -> &A {
+        self.iter.allocator()
+    }
+}
+
+impl<T, A: Allocator> Iterator for RawDrain<'_, T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for RawDrain<'_, T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for RawDrain<'_, T, A> {}
+
+impl<T, A: Allocator> FusedIterator for RawDrain<'_, T, A> {}
+
+impl<T, A: Allocator> Drop for RawDrain<'_, T, A> {
+    fn drop(&mut self) {
+        self.iter.by_ref().for_each(drop);
+        self.iter.table.clear_no_drop();
+        // 原处是迭代开始时放入的空表，交换后由 `iter` 析构
+        mem::swap(self.orig, &mut self.iter.table);
+    }
+}
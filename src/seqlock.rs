@@ -0,0 +1,352 @@
+//! 顺序锁（seqlock）映射：`Copy` 值的无锁乐观读取（需要启用 `concurrent` 特性）
+//!
+//! 表沿用分组布局：每个分组 `CHUNK_SIZE` 个控制字节、越界计数和 `CHUNK_SIZE` 个槽位，
+//! 另外附带一个版本计数。写者修改分组前把版本加一（变为奇数），改完再加一；
+//! 读者在读取控制字节和槽位前后各读一次版本，两次相同且为偶数才采用读到的数据，
+//! 否则重试。读者从不阻塞写者，也从不获取锁。
+//!
+//! 写者之间由一把互斥锁串行化。
+//!
+//! 与 [`F14Map`](crate::F14Map) 不同，这是一个**有界**的映射：容量在创建时固定，
+//! 永不扩容，超过容量的插入返回 [`MapError::CapacityExceeded`]。扩容意味着发布
+//! 新表并释放旧表，而读者不持有任何锁或引用计数，随时可能还在读旧表；安全释放
+//! 需要 epoch 或 hazard pointer 之类的回收机制，这里不引入。需要自动扩容时请使用
+//! [`ConcurrentF14Map`](crate::ConcurrentF14Map)（读者加读锁）。
+//!
+//! 槽位以 `AtomicU64` 字存放，读写都是逐字的原子操作，读者与写者并发访问同一
+//! 槽位不构成数据竞争。读者读到的可能是写到一半的字节，只有版本校验通过后才会
+//! 被当作 `K`/`V` 使用。因此键和值必须实现 [`NoUninit`]（`Copy` 且没有填充字节），
+//! 其字节可以完整地存入原子字，丢弃时也无需析构。
+
+use crate::{
+    error::MapError,
+    f14_map::make_hash,
+    probe_strategy::GroupProbeSeq,
+    raw_table::capacity_for,
+    simd_utils::{CHUNK_SIZE, EMPTY, OVERFLOW_SATURATED},
+    traits::BuildHasherExt,
+};
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    hash::Hash,
+    hint,
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ptr,
+    sync::{
+        atomic::{fence, AtomicU64, AtomicU8, AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+pub use bytemuck::NoUninit;
+
+const WORD: usize = mem::size_of::<u64>();
+
+/// 存放 `T` 的字节所需的原子字数
+const fn words_of<T>() -> usize {
+    mem::size_of::<T>().div_ceil(WORD)
+}
+
+/// 把 `value` 的字节逐字写入 `dst`，末尾不足一字的部分补零
+fn store_words<T: NoUninit>(dst: &[AtomicU64], value: &T) {
+    for (word, bytes) in dst.iter().zip(bytemuck::bytes_of(value).chunks(WORD)) {
+        let mut buf = [0; WORD];
+        buf[..bytes.len()].copy_from_slice(bytes);
+        word.store(u64::from_ne_bytes(buf), Ordering::Relaxed);
+    }
+}
+
+/// 从 `src` 逐字读出 `T` 的字节；只有版本校验通过后结果才是有效的 `T`
+fn load_words<T>(src: &[AtomicU64]) -> MaybeUninit<T> {
+    let mut out = MaybeUninit::<T>::uninit();
+    let dst = out.as_mut_ptr().cast::<u8>();
+    for (index, word) in src.iter().enumerate() {
+        let buf = word.load(Ordering::Relaxed).to_ne_bytes();
+        let len = (mem::size_of::<T>() - index * WORD).min(WORD);
+        // SAFETY: `src` 恰好有 `words_of::<T>()` 个字，写入范围不超过 `T` 的大小
+        unsafe { ptr::copy_nonoverlapping(buf.as_ptr(), dst.add(index * WORD), len) };
+    }
+    out
+}
+
+/// 带版本计数的分组
+struct Chunk<K, V> {
+    // 版本计数，奇数表示写者正在修改本组
+    seq: AtomicU64,
+    ctrl: [AtomicU8; CHUNK_SIZE],
+    // 越过本组继续探测的键数量（饱和计数，与 `ChunkMeta` 相同）
+    outbound: AtomicU8,
+    // 每个槽位依次存放键的字和值的字
+    slots: Box<[AtomicU64]>,
+    _marker: PhantomData<(K, V)>,
+}
+
+/// 在分组中乐观读取的结果
+struct ChunkRead<V> {
+    // 命中的槽位（组内下标）与值
+    found: Option<(usize, V)>,
+    outbound: u8,
+}
+
+impl<K: NoUninit, V: NoUninit> Chunk<K, V> {
+    const KEY_WORDS: usize = words_of::<K>();
+    const SLOT_WORDS: usize = words_of::<K>() + words_of::<V>();
+
+    fn new() -> Self {
+        Self {
+            seq: AtomicU64::new(0),
+            ctrl: std::array::from_fn(|_| AtomicU8::new(EMPTY)),
+            outbound: AtomicU8::new(0),
+            slots: (0..CHUNK_SIZE * Self::SLOT_WORDS).map(|_| AtomicU64::new(0)).collect(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// 槽位的键字和值字
+    fn slot_words(&self, index: usize) -> (&[AtomicU64], &[AtomicU64]) {
+        let start = index * Self::SLOT_WORDS;
+        self.slots[start..start + Self::SLOT_WORDS].split_at(Self::KEY_WORDS)
+    }
+
+    /// 在版本不变的前提下查找片段匹配且键满足 `eq` 的槽位
+    fn read(&self, fragment: u8, mut eq: impl FnMut(&K) -> bool) -> ChunkRead<V> {
+        'retry: loop {
+            let seq = self.seq.load(Ordering::Acquire);
+            if seq & 1 == 1 {
+                hint::spin_loop();
+                continue;
+            }
+
+            for index in 0..CHUNK_SIZE {
+                if self.ctrl[index].load(Ordering::Relaxed) != fragment {
+                    continue;
+                }
+                // 写者可能同时在修改该槽位，先复制再校验版本
+                let (key_words, value_words) = self.slot_words(index);
+                let key = load_words::<K>(key_words);
+                let value = load_words::<V>(value_words);
+                fence(Ordering::Acquire);
+                if self.seq.load(Ordering::Relaxed) != seq {
+                    continue 'retry;
+                }
+                // SAFETY: 版本未变，读到的字节就是写者在上一次完整写入中存入的键值
+                let (key, value) = unsafe { (key.assume_init(), value.assume_init()) };
+                if eq(&key) {
+                    return ChunkRead { found: Some((index, value)), outbound: 0 };
+                }
+            }
+
+            let outbound = self.outbound.load(Ordering::Relaxed);
+            fence(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == seq {
+                return ChunkRead { found: None, outbound };
+            }
+        }
+    }
+
+    /// 在版本计数的保护下修改本组（只能由持有写锁的写者调用）
+    fn write<R>(&self, f: impl FnOnce(&Self) -> R) -> R {
+        let seq = self.seq.load(Ordering::Relaxed);
+        self.seq.store(seq + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        let result = f(self);
+        self.seq.store(seq + 2, Ordering::Release);
+        result
+    }
+
+    /// 写入槽位（在 [`Chunk::write`] 内调用）
+    fn store_slot(&self, index: usize, fragment: u8, (key, value): (K, V)) {
+        let (key_words, value_words) = self.slot_words(index);
+        store_words(key_words, &key);
+        store_words(value_words, &value);
+        self.ctrl[index].store(fragment, Ordering::Relaxed);
+    }
+
+    fn find_empty(&self) -> Option<usize> {
+        (0..CHUNK_SIZE).find(|&index| self.ctrl[index].load(Ordering::Relaxed) == EMPTY)
+    }
+
+    fn inc_outbound(&self) {
+        let outbound = self.outbound.load(Ordering::Relaxed);
+        if outbound != OVERFLOW_SATURATED {
+            self.outbound.store(outbound + 1, Ordering::Relaxed);
+        }
+    }
+
+    fn dec_outbound(&self) {
+        let outbound = self.outbound.load(Ordering::Relaxed);
+        if outbound != OVERFLOW_SATURATED {
+            self.outbound.store(outbound - 1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// 读者无锁的有界映射
+///
+/// 容量在创建时固定且永不扩容，原因见[模块文档](self)。
+pub struct SeqLockF14Map<K, V, S = RandomState> {
+    chunks: Box<[Chunk<K, V>]>,
+    // 可容纳的最大元素数量
+    max_len: usize,
+    len: AtomicUsize,
+    // 串行化写者
+    writer: Mutex<()>,
+    hasher_builder: S,
+}
+
+impl<K, V, S> SeqLockF14Map<K, V, S>
+where
+    K: NoUninit + Eq + Hash,
+    V: NoUninit,
+    S: BuildHasherExt + Default,
+{
+    /// 创建最多容纳 `capacity` 个元素的映射（之后不会扩容）
+    pub fn with_capacity(capacity: usize) -> Result<Self, MapError> {
+        Self::with_capacity_and_hasher(capacity, S::default())
+    }
+}
+
+impl<K, V, S> SeqLockF14Map<K, V, S>
+where
+    K: NoUninit + Eq + Hash,
+    V: NoUninit,
+    S: BuildHasherExt,
+{
+    /// 使用指定的哈希构建器创建最多容纳 `capacity` 个元素的映射
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Result<Self, MapError> {
+        let slots = capacity_for(capacity.max(1)).ok_or(MapError::CapacityExceeded)?;
+        let chunks = (0..slots / CHUNK_SIZE).map(|_| Chunk::new()).collect();
+        Ok(Self {
+            chunks,
+            max_len: capacity,
+            len: AtomicUsize::new(0),
+            writer: Mutex::new(()),
+            hasher_builder: hasher,
+        })
+    }
+
+    /// 可容纳的最大元素数量
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.max_len
+    }
+
+    /// 元素数量
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// 检查是否为空
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 沿探测序列乐观查找键，返回全局槽位索引和值
+    fn locate<Q>(&self, full_hash: u64, fragment: u8, key: &Q) -> Option<(usize, V)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        for group_index in GroupProbeSeq::new(full_hash, fragment, self.chunks.len()) {
+            let read = self.chunks[group_index].read(fragment, |k| k.borrow() == key);
+            if let Some((index, value)) = read.found {
+                return Some((group_index * CHUNK_SIZE + index, value));
+            }
+            if read.outbound == 0 {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// 获取值的副本（无锁）
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (full_hash, fragment) = make_hash(&self.hasher_builder, key);
+        self.locate(full_hash, fragment, key).map(|(_, value)| value)
+    }
+
+    /// 检查键是否存在（无锁）
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// 插入或覆盖键值对，返回旧值
+    ///
+    /// 表中已有 [`capacity`](Self::capacity) 个元素时，插入新键返回
+    /// [`MapError::CapacityExceeded`]；覆盖已有键总是成功。
+    pub fn insert(&self, key: K, value: V) -> Result<Option<V>, MapError> {
+        // 写者只在版本计数保护内修改分组，中途不会 panic，中毒的锁可以直接继续使用
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let (full_hash, fragment) = make_hash(&self.hasher_builder, &key);
+
+        if let Some((slot, old)) = self.locate(full_hash, fragment, &key) {
+            let chunk = &self.chunks[slot / CHUNK_SIZE];
+            chunk.write(|chunk| chunk.store_slot(slot % CHUNK_SIZE, fragment, (key, value)));
+            return Ok(Some(old));
+        }
+        if self.len() == self.max_len {
+            return Err(MapError::CapacityExceeded);
+        }
+
+        // 先记录途经分组的越界计数，键写入后读者才能沿探测序列找到它
+        for group_index in GroupProbeSeq::new(full_hash, fragment, self.chunks.len()) {
+            let chunk = &self.chunks[group_index];
+            if let Some(index) = chunk.find_empty() {
+                chunk.write(|chunk| chunk.store_slot(index, fragment, (key, value)));
+                self.len.fetch_add(1, Ordering::Relaxed);
+                return Ok(None);
+            }
+            chunk.inc_outbound();
+        }
+        unreachable!("table sized for max_len always has a free slot")
+    }
+
+    /// 移除键，返回旧值
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let (full_hash, fragment) = make_hash(&self.hasher_builder, key);
+        let (slot, value) = self.locate(full_hash, fragment, key)?;
+
+        let target_group = slot / CHUNK_SIZE;
+        self.chunks[target_group].write(|chunk| chunk.ctrl[slot % CHUNK_SIZE].store(EMPTY, Ordering::Relaxed));
+        // 键已不可见，再撤销途经分组的越界计数
+        for group_index in GroupProbeSeq::new(full_hash, fragment, self.chunks.len()) {
+            if group_index == target_group {
+                break;
+            }
+            self.chunks[group_index].dec_outbound();
+        }
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        Some(value)
+    }
+
+    /// 清空映射
+    pub fn clear(&self) {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        for chunk in self.chunks.iter() {
+            chunk.write(|chunk| {
+                for ctrl in &chunk.ctrl {
+                    ctrl.store(EMPTY, Ordering::Relaxed);
+                }
+                chunk.outbound.store(0, Ordering::Relaxed);
+            });
+        }
+        self.len.store(0, Ordering::Relaxed);
+    }
+}
@@ -9,24 +9,100 @@ pub const EMPTY: u8 = 0b1000_0000;     // -128
 pub const DELETED: u8 = 0b1000_0001;   // -127
 pub const FULL_MASK: u8 = 0b0111_1111; // 所有设置位的掩码
 
+/// 溢出计数饱和值：达到后不再递减，探测会一直越过该组
+pub const OVERFLOW_SATURATED: u8 = u8::MAX;
+
+/// 分组溢出元数据（对应 Folly F14 chunk 中的 outbound/hosted overflow count）
+///
+/// `outbound` 记录起始组探测经过本组、最终落在后续组的键数量；
+/// 查找时若本组未命中且 `outbound == 0`，说明不可能有键越过本组，探测即可终止。
+/// `hosted` 记录本组中起始组不是本组的键数量。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkMeta {
+    outbound: u8,
+    hosted: u8,
+}
+
+impl ChunkMeta {
+    /// 越过本组继续探测的键数量（饱和计数）
+    #[inline]
+    pub fn outbound_overflow(&self) -> u8 {
+        self.outbound
+    }
+
+    /// 本组托管的、来自其他起始组的键数量
+    #[inline]
+    pub fn hosted_overflow(&self) -> u8 {
+        self.hosted
+    }
+
+    /// 增加越界计数，饱和后保持不变
+    #[inline]
+    pub fn inc_outbound(&mut self) {
+        if self.outbound != OVERFLOW_SATURATED {
+            self.outbound += 1;
+        }
+    }
+
+    /// 减少越界计数，饱和后不再递减
+    #[inline]
+    pub fn dec_outbound(&mut self) {
+        if self.outbound != OVERFLOW_SATURATED {
+            self.outbound -= 1;
+        }
+    }
+
+    /// 越界计数直接置为饱和，探测总会越过本组
+    #[inline]
+    pub(crate) fn saturate_outbound(&mut self) {
+        self.outbound = OVERFLOW_SATURATED;
+    }
+
+    /// 增加托管计数
+    #[inline]
+    pub fn inc_hosted(&mut self) {
+        self.hosted += 1;
+    }
+
+    /// 减少托管计数
+    #[inline]
+    pub fn dec_hosted(&mut self) {
+        self.hosted -= 1;
+    }
+}
+
 /// SIMD策略trait
+///
+/// 方法签名保持安全以兼容已有实现；`ctrls` 必须指向足够的控制字节（查找类方法至少
+/// `CHUNK_SIZE` 个可读字节，填充至少 `count` 个可写字节）。crate 内部通过带
+/// `# Safety` 约定的 `simd_*` 函数调用这些方法。
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub trait SimdStrategy {
+    /// 查找第一个匹配片段的槽位
     fn find_match(ctrls: *const u8, fragment: u8) -> Option<usize>;
+    /// 查找第一个空闲（EMPTY 或 DELETED）槽位
     fn find_empty(ctrls: *const u8) -> Option<usize>;
+    /// 用指定值填充控制字节
     fn fill_ctrls(ctrls: *mut u8, value: u8, count: usize);
+    /// FULL 槽位的位掩码，第 `i` 位对应组内第 `i` 个槽位
+    ///
+    /// 默认逐字节检查，SIMD 实现覆盖为一次比较。
+    #[inline]
+    fn match_full(ctrls: *const u8) -> u16 {
+        (0..CHUNK_SIZE)
+            .filter(|&i| unsafe { *ctrls.add(i) } & !FULL_MASK == 0)
+            .fold(0, |mask, i| mask | (1 << i))
+    }
 }
 
 /// 标量降级实现
 pub struct Scalar;
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 impl SimdStrategy for Scalar {
     #[inline]
     fn find_match(ctrls: *const u8, fragment: u8) -> Option<usize> {
-        for i in 0..CHUNK_SIZE {
-            if unsafe { *ctrls.add(i) } == fragment {
-                return Some(i);
-            }
-        }
-        None
+        (0..CHUNK_SIZE).find(|&i| unsafe { *ctrls.add(i) } == fragment)
     }
     
     #[inline]
@@ -39,7 +115,7 @@ impl SimdStrategy for Scalar {
         }
         None
     }
-    
+
     #[inline]
     fn fill_ctrls(ctrls: *mut u8, value: u8, count: usize) {
         for i in 0..count {
@@ -84,6 +160,17 @@ impl SimdStrategy for Sse2 {
             if mask != 0 { Some(mask.trailing_zeros() as usize) } else { None }
         }
     }
+
+    #[inline]
+    fn match_full(ctrls: *const u8) -> u16 {
+        unsafe {
+            use std::arch::x86_64::*;
+
+            // EMPTY 与 DELETED 的最高位为 1，FULL 的最高位为 0
+            let ctrl_vec = _mm_loadu_si128(ctrls as *const __m128i);
+            !(_mm_movemask_epi8(ctrl_vec) as u16)
+        }
+    }
     
     #[inline]
     fn fill_ctrls(ctrls: *mut u8, value: u8, count: usize) {
@@ -114,36 +201,21 @@ impl SimdStrategy for Sse2 {
 pub struct Avx2;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 impl SimdStrategy for Avx2 {
+    // 一个分组只有 16 个控制字节，256 位加载会越过分组甚至越过分配末尾，
+    // 组内比较沿用 128 位实现，AVX2 只用于批量填充。
     #[inline]
     fn find_match(ctrls: *const u8, fragment: u8) -> Option<usize> {
-        unsafe {
-            use std::arch::x86_64::*;
-            
-            let ctrl_vec = _mm256_loadu_si256(ctrls as *const __m256i);
-            let frag_vec = _mm256_set1_epi8(fragment as i8);
-            let match_vec = _mm256_cmpeq_epi8(ctrl_vec, frag_vec);
-            let mask = _mm256_movemask_epi8(match_vec) as u32;
-            
-            if mask != 0 { Some(mask.trailing_zeros() as usize) } else { None }
-        }
+        Sse2::find_match(ctrls, fragment)
     }
     
     #[inline]
     fn find_empty(ctrls: *const u8) -> Option<usize> {
-        unsafe {
-            use std::arch::x86_64::*;
-            
-            let empty_vec = _mm256_set1_epi8(EMPTY as i8);
-            let deleted_vec = _mm256_set1_epi8(DELETED as i8);
-            
-            let ctrl_vec = _mm256_loadu_si256(ctrls as *const __m256i);
-            let empty_match = _mm256_cmpeq_epi8(ctrl_vec, empty_vec);
-            let deleted_match = _mm256_cmpeq_epi8(ctrl_vec, deleted_vec);
-            let combined = _mm256_or_si256(empty_match, deleted_match);
-            
-            let mask = _mm256_movemask_epi8(combined) as u32;
-            if mask != 0 { Some(mask.trailing_zeros() as usize) } else { None }
-        }
+        Sse2::find_empty(ctrls)
+    }
+
+    #[inline]
+    fn match_full(ctrls: *const u8) -> u16 {
+        Sse2::match_full(ctrls)
     }
     
     #[inline]
@@ -199,6 +271,24 @@ pub fn make_ctrl_byte(hash_frag: u8) -> u8 {
     hash_frag & FULL_MASK
 }
 
+/// 预取 `ptr` 所在的缓存行（只读），不支持的平台上为空操作
+///
+/// 预取只是提示，不会解引用指针，因此对任意地址调用都是安全的。
+#[inline(always)]
+pub fn prefetch_read<T>(ptr: *const T) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        // SSE 是 x86_64 的基线特性
+        std::arch::x86_64::_mm_prefetch::<{ std::arch::x86_64::_MM_HINT_T0 }>(ptr.cast());
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = ptr;
+}
+
+/// 查找空闲槽位
+///
+/// # Safety
+/// `ctrls` 必须指向至少 `CHUNK_SIZE` 个可读的控制字节。
 #[inline]
 pub unsafe fn simd_find_empty(ctrls: *const u8) -> Option<usize> {
     dispatch_simd!(
@@ -208,6 +298,9 @@ pub unsafe fn simd_find_empty(ctrls: *const u8) -> Option<usize> {
 }
 
 /// 查找匹配片段的位置
+///
+/// # Safety
+/// `ctrls` 必须指向至少 `CHUNK_SIZE` 个可读的控制字节。
 #[inline]
 pub unsafe fn simd_find_match(ctrls: *const u8, fragment: u8) -> Option<usize> {
     dispatch_simd!(
@@ -217,17 +310,40 @@ pub unsafe fn simd_find_match(ctrls: *const u8, fragment: u8) -> Option<usize> {
     )
 }
 
+/// 组内 FULL 槽位的位掩码
+///
+/// 迭代时每个分组都会调用一次；编译期已启用 SSE2 时（x86_64 的基线）直接使用 SSE2，
+/// 省去运行时特性检测。
+///
+/// # Safety
+/// `ctrls` 必须指向至少 `CHUNK_SIZE` 个可读的控制字节。
+#[inline]
+pub unsafe fn simd_match_full(ctrls: *const u8) -> u16 {
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    {
+        Sse2::match_full(ctrls)
+    }
+    #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2")))]
+    {
+        dispatch_simd!(
+            match_full,
+            ctrls
+        )
+    }
+}
+
 
 /// 查找所有匹配片段的位置
+///
+/// # Safety
+/// `ctrls` 必须指向至少 `CHUNK_SIZE` 个可读的控制字节。
 #[inline]
 pub unsafe fn find_all_matches(ctrls: *const u8, fragment: u8) -> [u8; CHUNK_SIZE] {
     unsafe {
          // 平台特性检测
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
      {
-        if is_x86_feature_detected!("avx2") {
-            return avx2_find_all_matches(ctrls, fragment);
-        } else if is_x86_feature_detected!("sse2") {
+        if is_x86_feature_detected!("sse2") {
             return sse2_find_all_matches(ctrls, fragment);
         }
     }
@@ -268,35 +384,6 @@ unsafe fn sse2_find_all_matches(ctrls: *const u8, fragment: u8) -> [u8; CHUNK_SI
     matches
 }
 
-/// AVX2 实现 (x86/x86_64)
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-#[target_feature(enable = "avx2")]
-unsafe fn avx2_find_all_matches(ctrls: *const u8, fragment: u8) -> [u8; CHUNK_SIZE] {
-    use std::arch::x86_64::*;
-    
-    let ctrl_vec = unsafe { _mm256_loadu_si256(ctrls as *const __m256i) };
-    let frag_vec = _mm256_set1_epi8(fragment as i8);
-    let match_vec = _mm256_cmpeq_epi8(ctrl_vec, frag_vec);
-    let mask = _mm256_movemask_epi8(match_vec) as u32;
-    
-    let mut matches = [0xFF; CHUNK_SIZE]; // 初始化为无效值
-    
-    if mask != 0 {
-        let mut bitmask = mask;
-        let mut count = 0;
-        
-        while bitmask != 0 && count < CHUNK_SIZE {
-            let index = bitmask.trailing_zeros() as u8;
-            matches[count] = index;
-            count += 1;
-            bitmask &= bitmask - 1; // 清除最低位的1
-        }
-    }
-    
-    matches
-}
-
-
 /// 标量回退实现
 unsafe fn scalar_find_all_matches(ctrls: *const u8, fragment: u8) -> [u8; CHUNK_SIZE] {
     let mut matches = [0xFF; CHUNK_SIZE]; // 初始化为无效值
@@ -0,0 +1,147 @@
+//! 读多写少的快照映射（RCU 风格）
+//!
+//! [`SnapshotF14Map`] 始终对外发布一个不可变的 [`F14VectorMap`] 版本。读者用
+//! [`SnapshotF14Map::load`] 取得当前版本的 `Arc` 引用（只增加一次引用计数），
+//! 之后的查找不持有任何锁；写者基于当前版本和一批更新构建新版本，构建完成后
+//! 一次性替换发布。旧版本在最后一个读者释放 [`Snapshot`] 时被回收。
+//!
+//! 同一时刻只有一个写者在构建新版本，批量更新之间不会互相覆盖。
+//!
+//! 版本号就是所发布映射的 [`F14Map::batch_version`](crate::F14Map::batch_version)：每次发布
+//! 通过一次 [`apply_batch`](crate::F14Map::apply_batch) 构建新版本，版本号随之加一。
+
+use crate::{error::MapError, f14_map::F14VectorMap, traits::BuildHasherExt};
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    hash::Hash,
+    ops::Deref,
+    sync::{Mutex, PoisonError, RwLock},
+};
+use triomphe::Arc;
+
+/// RCU 风格的快照映射
+pub struct SnapshotF14Map<K, V, S = RandomState> {
+    // 当前发布的版本，锁只在读取或替换 `Arc` 时短暂持有
+    current: RwLock<Arc<F14VectorMap<K, V, S>>>,
+    // 串行化写者
+    writer: Mutex<()>,
+}
+
+/// 某一版本的只读视图，持有期间该版本不会被回收
+///
+/// 通过 `Deref` 访问该版本的映射，版本号即 [`F14Map::batch_version`](crate::F14Map::batch_version)。
+pub struct Snapshot<K, V, S = RandomState> {
+    inner: Arc<F14VectorMap<K, V, S>>,
+}
+
+impl<K, V, S> Clone for Snapshot<K, V, S> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<K, V, S> Deref for Snapshot<K, V, S> {
+    type Target = F14VectorMap<K, V, S>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<K, V, S> SnapshotF14Map<K, V, S>
+where
+    S: BuildHasherExt + Default,
+{
+    /// 创建一个空的快照映射
+    pub fn new() -> Result<Self, MapError> {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S> SnapshotF14Map<K, V, S>
+where
+    S: BuildHasherExt,
+{
+    /// 使用指定的哈希构建器创建空的快照映射
+    pub fn with_hasher(hasher: S) -> Result<Self, MapError> {
+        Ok(Self::from_map(F14VectorMap::with_hasher(hasher)?))
+    }
+
+    /// 以已有映射作为初始版本（沿用该映射的版本号）
+    pub fn from_map(map: F14VectorMap<K, V, S>) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(map)),
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// 获取当前版本的快照
+    pub fn load(&self) -> Snapshot<K, V, S> {
+        // 锁内只有 `Arc` 的读取和替换，不会因 panic 留下不一致的状态
+        let current = self.current.read().unwrap_or_else(PoisonError::into_inner);
+        Snapshot { inner: current.clone() }
+    }
+
+    /// 当前发布的版本号，即当前版本映射的 [`F14Map::batch_version`](crate::F14Map::batch_version)
+    pub fn version(&self) -> u64 {
+        self.load().batch_version()
+    }
+}
+
+impl<K, V, S> SnapshotF14Map<K, V, S>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    S: BuildHasherExt + Clone,
+{
+    /// 在当前版本中查找并克隆值
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.load().get(key).cloned()
+    }
+
+    /// 基于当前版本应用一批更新并发布新版本，返回新版本号
+    ///
+    /// `Some(value)` 为写入，`None` 为删除。构建过程中出错时不发布任何内容，
+    /// 读者继续看到原来的版本。
+    pub fn publish<I>(&self, batch: I) -> Result<u64, MapError>
+    where
+        I: IntoIterator<Item = (K, Option<V>)>,
+    {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let current = self.load();
+
+        // 直接复制分组表布局（连同版本号），不必逐个重新插入
+        let mut map = current.try_clone()?;
+        let version = map.apply_batch(batch)?.batch_version;
+
+        let next = Arc::new(map);
+        let previous = {
+            let mut slot = self.current.write().unwrap_or_else(PoisonError::into_inner);
+            std::mem::replace(&mut *slot, next)
+        };
+        // 在锁外释放旧版本的引用，最后一个读者负责回收
+        drop(previous);
+        Ok(version)
+    }
+}
+
+impl<K, V, S> std::fmt::Debug for SnapshotF14Map<K, V, S>
+where
+    K: std::fmt::Debug,
+    V: std::fmt::Debug,
+    S: BuildHasherExt,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let snapshot = self.load();
+        f.debug_struct("SnapshotF14Map")
+            .field("version", &snapshot.batch_version())
+            .field("map", &*snapshot)
+            .finish()
+    }
+}
@@ -0,0 +1,1208 @@
+//! 存储策略的具体实现
+//!
+//! 所有布局共用 [`RawTable`] 的控制字节、溢出元数据与探测代码，区别只在于槽位中存放什么：
+//! - [`TableStorage<K, V, (K, V)>`]：槽位直接存放键值对（值策略）
+//! - [`TableStorage<K, V, Node<K, V>>`]：槽位存放指向键值对的指针，扩容只搬移指针（节点策略）
+//! - [`VectorStorage`]：槽位存放 32 位下标，键值对连续存放在稠密数组中（向量策略）
+//! - [`FastStorage`]：按键值对大小在值策略与向量策略之间选择
+//!
+//! 分组表、稠密数组与节点的内存都通过分配器 `A` 分配。
+
+use crate::{
+    allocator::Allocator,
+    dense_array::{self, DenseArray},
+    error::MapError,
+    raw_table::{max_len, RawDrain, RawIntoIter, RawIter, RawTable, SlotState},
+    simd_utils::ChunkMeta,
+};
+use std::{
+    alloc::Layout, iter::FusedIterator, marker::PhantomData, mem, ptr::{self, NonNull}, slice
+};
+
+/// 分组槽位中保存的下标类型，限制了向量策略最多容纳的元素数量
+const MAX_INDEX: usize = u32::MAX as usize;
+
+/// 封闭 [`crate::policy::StoragePolicy`]，外部无法实现新的策略
+pub trait Sealed {}
+
+/// 存储布局需要向映射提供的操作
+///
+/// `slot` 参数均为分组表中的槽位索引；需要哈希的操作通过 `hash` 回调计算
+/// `(full_hash, fragment)`，存储本身不持有哈希构建器。
+pub trait RawStorage<K, V, A: Allocator + Clone>: Sized {
+    /// 按存储顺序产出 `&(K, V)` 的迭代器
+    type Iter<'a>: Iterator<Item = &'a (K, V)>
+        + DoubleEndedIterator
+        + ExactSizeIterator
+        + FusedIterator
+        + Clone
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    /// 按存储顺序产出 `&mut (K, V)` 的迭代器
+    type IterMut<'a>: Iterator<Item = &'a mut (K, V)>
+        + DoubleEndedIterator
+        + ExactSizeIterator
+        + FusedIterator
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    /// 按存储顺序移出键值对的迭代器
+    type IntoIter: Iterator<Item = (K, V)> + DoubleEndedIterator + ExactSizeIterator + FusedIterator;
+
+    /// 移出键值对但保留内存的迭代器
+    type Drain<'a>: Iterator<Item = (K, V)> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    /// 创建不分配内存的空存储
+    fn new_in(alloc: A) -> Self;
+
+    /// 创建至少能容纳 `capacity` 个槽位的存储
+    fn with_capacity_in(capacity: usize, alloc: A) -> Result<Self, MapError>;
+
+    /// 存储使用的分配器
+    fn allocator(&self) -> &A;
+
+    /// 槽位总数
+    fn capacity(&self) -> usize;
+
+    /// 分组数量
+    fn group_count(&self) -> usize;
+
+    /// 元素数量
+    fn len(&self) -> usize;
+
+    /// 是否为空
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 删除标记数量
+    fn deleted(&self) -> usize;
+
+    /// 是否需要扩容才能再插入一个元素
+    fn needs_grow(&self) -> bool;
+
+    /// 指定分组的溢出元数据
+    fn chunk_meta(&self, group_index: usize) -> ChunkMeta;
+
+    /// 槽位状态
+    fn slot_state(&self, slot: usize) -> SlotState;
+
+    /// 沿探测序列查找键满足 `eq` 的槽位
+    fn find(&self, full_hash: u64, fragment: u8, eq: impl FnMut(&K) -> bool) -> Option<usize>;
+
+    /// 预取 `find` 将首先访问的控制字节和槽位
+    fn prefetch(&self, full_hash: u64, fragment: u8);
+
+    /// 分组中 FULL 槽位的位掩码
+    fn full_mask(&self, group_index: usize) -> u16;
+
+    /// 沿探测序列查找首个空闲槽位
+    fn find_insert_slot(&self, full_hash: u64, fragment: u8) -> Option<usize>;
+
+    /// 槽位对应的键值对
+    fn pair(&self, slot: usize) -> &(K, V);
+
+    /// 槽位对应的键值对（可变）
+    fn pair_mut(&mut self, slot: usize) -> &mut (K, V);
+
+    /// 槽位对应键值对的指针，供并行迭代在互不相交的槽位上同时访问
+    ///
+    /// # Safety
+    /// 槽位必须为 FULL；通过指针写入时，调用方必须保证没有其他线程访问同一槽位。
+    #[cfg(feature = "rayon")]
+    unsafe fn pair_ptr(&self, slot: usize) -> NonNull<(K, V)>;
+
+    /// 移出槽位中的键值对，不修改控制字节
+    ///
+    /// # Safety
+    /// 槽位必须为 FULL，移出后不能再被访问；全部移出后调用 [`forget_all`](Self::forget_all)。
+    #[cfg(feature = "rayon")]
+    unsafe fn read_pair(&self, slot: usize) -> (K, V);
+
+    /// 清空所有槽位但不析构元素，保留内存（尚未移出的元素被泄漏）
+    #[cfg(feature = "rayon")]
+    fn forget_all(&mut self);
+
+    /// 在空闲槽位写入键值对，失败时存储保持不变
+    fn insert_at(&mut self, slot: usize, full_hash: u64, fragment: u8, pair: (K, V)) -> Result<(), MapError>;
+
+    /// 移出槽位中的键值对，槽位恢复为 EMPTY
+    fn erase_at(
+        &mut self,
+        slot: usize,
+        full_hash: u64,
+        fragment: u8,
+        hash: impl Fn(&K) -> (u64, u8),
+    ) -> (K, V);
+
+    /// 移出槽位中的键值对并留下删除标记
+    fn tombstone_at(&mut self, slot: usize, hash: impl Fn(&K) -> (u64, u8)) -> (K, V);
+
+    /// 将所有元素重新放入容量为 `capacity` 的分组表（扩容与重建共用）
+    ///
+    /// 失败时存储保持不变。
+    fn rehash(&mut self, capacity: usize, hash: impl Fn(&K) -> (u64, u8)) -> Result<(), MapError>;
+
+    /// 在原有内存中重新放置所有元素，清除墓碑并重建溢出元数据
+    fn rehash_in_place(&mut self, hash: impl Fn(&K) -> (u64, u8));
+
+    /// 克隆出布局相同的存储：控制字节与溢出元数据直接复制，不重新插入
+    fn try_clone(&self) -> Result<Self, MapError>
+    where
+        K: Clone,
+        V: Clone;
+
+    /// 析构所有元素，保留内存
+    fn clear(&mut self);
+
+    fn iter(&self) -> Self::Iter<'_>;
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_>;
+
+    fn into_iter(self) -> Self::IntoIter;
+
+    /// 移出所有键值对，迭代器析构后存储为空但保留内存
+    fn drain(&mut self) -> Self::Drain<'_>;
+}
+
+/// 直接存放在分组表槽位中的数据
+pub trait PairSlot<K, V>: Sized {
+    fn pair(&self) -> &(K, V);
+    fn pair_mut(&mut self) -> &mut (K, V);
+}
+
+/// 槽位数据的创建与释放，需要内存时通过分配器 `A` 分配
+pub trait AllocSlot<K, V, A: Allocator>: PairSlot<K, V> {
+    /// 释放槽位时是否需要逐个调用 [`AllocSlot::into_pair`]
+    const NEEDS_RELEASE: bool;
+
+    /// 包装键值对，需要分配内存时失败返回错误
+    fn try_from_pair(pair: (K, V), alloc: &A) -> Result<Self, MapError>;
+
+    /// 取出键值对并释放槽位数据自身占用的内存
+    ///
+    /// # Safety
+    /// `alloc` 必须是 `try_from_pair` 使用的分配器或其克隆。
+    unsafe fn into_pair(self, alloc: &A) -> (K, V);
+}
+
+impl<K, V> PairSlot<K, V> for (K, V) {
+    #[inline]
+    fn pair(&self) -> &(K, V) {
+        self
+    }
+
+    #[inline]
+    fn pair_mut(&mut self) -> &mut (K, V) {
+        self
+    }
+}
+
+impl<K, V, A: Allocator> AllocSlot<K, V, A> for (K, V) {
+    const NEEDS_RELEASE: bool = mem::needs_drop::<(K, V)>();
+
+    #[inline]
+    fn try_from_pair(pair: (K, V), _alloc: &A) -> Result<Self, MapError> {
+        Ok(pair)
+    }
+
+    #[inline]
+    unsafe fn into_pair(self, _alloc: &A) -> (K, V) {
+        self
+    }
+}
+
+/// 节点策略的槽位：指向单独分配的键值对
+///
+/// 节点内存通过映射的分配器分配，只能由 [`AllocSlot::into_pair`] 释放。
+pub struct Node<K, V> {
+    ptr: NonNull<(K, V)>,
+    marker: PhantomData<(K, V)>,
+}
+
+// 节点独占其键值对
+unsafe impl<K: Send, V: Send> Send for Node<K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for Node<K, V> {}
+
+impl<K, V> PairSlot<K, V> for Node<K, V> {
+    #[inline]
+    fn pair(&self) -> &(K, V) {
+        unsafe { self.ptr.as_ref() }
+    }
+
+    #[inline]
+    fn pair_mut(&mut self) -> &mut (K, V) {
+        unsafe { &mut *self.ptr.as_ptr() }
+    }
+}
+
+impl<K, V, A: Allocator> AllocSlot<K, V, A> for Node<K, V> {
+    const NEEDS_RELEASE: bool = true;
+
+    fn try_from_pair(pair: (K, V), alloc: &A) -> Result<Self, MapError> {
+        // 手动分配以便报告错误，零大小的键值对不占内存
+        let layout = Layout::new::<(K, V)>();
+        let ptr: NonNull<(K, V)> = if layout.size() == 0 {
+            NonNull::dangling()
+        } else {
+            alloc
+                .allocate(layout)
+                .map_err(|_| MapError::AllocationFailed { layout })?
+                .cast()
+        };
+        unsafe { ptr.as_ptr().write(pair) };
+        Ok(Self { ptr, marker: PhantomData })
+    }
+
+    #[inline]
+    unsafe fn into_pair(self, alloc: &A) -> (K, V) {
+        let layout = Layout::new::<(K, V)>();
+        let pair = unsafe { self.ptr.as_ptr().read() };
+        if layout.size() != 0 {
+            unsafe { alloc.deallocate(self.ptr.cast(), layout) };
+        }
+        pair
+    }
+}
+
+/// 槽位中直接存放数据的存储（值策略与节点策略）
+pub struct TableStorage<K, V, T: AllocSlot<K, V, A>, A: Allocator> {
+    table: RawTable<T, A>,
+    marker: PhantomData<(K, V)>,
+}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> TableStorage<K, V, T, A> {
+    /// 取出并释放所有槽位，保留分组表内存
+    fn release_all(&mut self) {
+        if T::NEEDS_RELEASE {
+            for slot in self.table.raw_iter() {
+                let value = unsafe { ptr::read(slot.as_ptr()) };
+                drop(unsafe { value.into_pair(self.table.allocator()) });
+            }
+        }
+        self.table.clear_no_drop();
+    }
+}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> Drop for TableStorage<K, V, T, A> {
+    fn drop(&mut self) {
+        self.release_all();
+    }
+}
+
+/// 重新插入过程中哈希函数 panic 时，新表中已搬入的元素仍由旧表析构
+struct ForgetOnUnwind<'a, T, A: Allocator>(&'a mut RawTable<T, A>);
+
+impl<T, A: Allocator> Drop for ForgetOnUnwind<'_, T, A> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.0.clear_no_drop();
+        }
+    }
+}
+
+impl<K, V, T, A> RawStorage<K, V, A> for TableStorage<K, V, T, A>
+where
+    T: AllocSlot<K, V, A>,
+    A: Allocator + Clone,
+{
+    type Iter<'a> = TableIter<'a, K, V, T> where Self: 'a, K: 'a, V: 'a;
+    type IterMut<'a> = TableIterMut<'a, K, V, T> where Self: 'a, K: 'a, V: 'a;
+    type IntoIter = TableIntoIter<K, V, T, A>;
+    type Drain<'a> = TableDrain<'a, K, V, T, A> where Self: 'a, K: 'a, V: 'a;
+
+    fn new_in(alloc: A) -> Self {
+        Self { table: RawTable::new_in(alloc), marker: PhantomData }
+    }
+
+    fn with_capacity_in(capacity: usize, alloc: A) -> Result<Self, MapError> {
+        Ok(Self { table: RawTable::with_capacity_in(capacity, alloc)?, marker: PhantomData })
+    }
+
+    #[inline]
+    fn allocator(&self) -> &A {
+        self.table.allocator()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.table.capacity()
+    }
+
+    #[inline]
+    fn group_count(&self) -> usize {
+        self.table.group_count()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    #[inline]
+    fn deleted(&self) -> usize {
+        self.table.deleted()
+    }
+
+    #[inline]
+    fn needs_grow(&self) -> bool {
+        self.table.needs_grow()
+    }
+
+    #[inline]
+    fn chunk_meta(&self, group_index: usize) -> ChunkMeta {
+        self.table.chunk_meta(group_index)
+    }
+
+    #[inline]
+    fn slot_state(&self, slot: usize) -> SlotState {
+        self.table.slot_state(slot)
+    }
+
+    #[inline]
+    fn find(&self, full_hash: u64, fragment: u8, mut eq: impl FnMut(&K) -> bool) -> Option<usize> {
+        self.table.find(full_hash, fragment, |slot| eq(&slot.pair().0))
+    }
+
+    #[inline]
+    fn prefetch(&self, full_hash: u64, fragment: u8) {
+        self.table.prefetch(full_hash, fragment)
+    }
+
+    #[inline]
+    fn full_mask(&self, group_index: usize) -> u16 {
+        self.table.full_mask(group_index)
+    }
+
+    #[inline]
+    fn find_insert_slot(&self, full_hash: u64, fragment: u8) -> Option<usize> {
+        self.table.find_insert_slot(full_hash, fragment)
+    }
+
+    #[inline]
+    fn pair(&self, slot: usize) -> &(K, V) {
+        unsafe { self.table.slot(slot) }.pair()
+    }
+
+    #[inline]
+    fn pair_mut(&mut self, slot: usize) -> &mut (K, V) {
+        unsafe { self.table.slot_mut(slot) }.pair_mut()
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    unsafe fn pair_ptr(&self, slot: usize) -> NonNull<(K, V)> {
+        // 经由表的槽位指针而不是共享引用，写入才合法
+        NonNull::from(unsafe { &mut *self.table.slot_ptr(slot).as_ptr() }.pair_mut())
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    unsafe fn read_pair(&self, slot: usize) -> (K, V) {
+        unsafe { ptr::read(self.table.slot_ptr(slot).as_ptr()).into_pair(self.table.allocator()) }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn forget_all(&mut self) {
+        self.table.clear_no_drop();
+    }
+
+    #[inline]
+    fn insert_at(&mut self, slot: usize, full_hash: u64, fragment: u8, pair: (K, V)) -> Result<(), MapError> {
+        let slot_value = T::try_from_pair(pair, self.table.allocator())?;
+        self.table.insert_at(slot, full_hash, fragment, slot_value);
+        Ok(())
+    }
+
+    #[inline]
+    fn erase_at(
+        &mut self,
+        slot: usize,
+        full_hash: u64,
+        fragment: u8,
+        _hash: impl Fn(&K) -> (u64, u8),
+    ) -> (K, V) {
+        let value = self.table.erase_at(slot, full_hash, fragment);
+        unsafe { value.into_pair(self.table.allocator()) }
+    }
+
+    #[inline]
+    fn tombstone_at(&mut self, slot: usize, _hash: impl Fn(&K) -> (u64, u8)) -> (K, V) {
+        let value = self.table.tombstone_at(slot);
+        unsafe { value.into_pair(self.table.allocator()) }
+    }
+
+    fn rehash(&mut self, capacity: usize, hash: impl Fn(&K) -> (u64, u8)) -> Result<(), MapError> {
+        // 先分配新表，分配失败时原表保持不变
+        let mut table = RawTable::with_capacity_in(capacity, self.table.allocator().clone())?;
+        {
+            let guard = ForgetOnUnwind(&mut table);
+            for slot in self.table.raw_iter() {
+                let (full_hash, fragment) = hash(&unsafe { slot.as_ref() }.pair().0);
+                let index = guard
+                    .0
+                    .find_insert_slot(full_hash, fragment)
+                    .expect("rehashed table must have a free slot");
+                guard.0.insert_at(index, full_hash, fragment, unsafe { ptr::read(slot.as_ptr()) });
+            }
+        }
+        // 元素已全部搬入新表，旧表只需释放内存
+        self.table.clear_no_drop();
+        self.table = table;
+        Ok(())
+    }
+
+    fn rehash_in_place(&mut self, hash: impl Fn(&K) -> (u64, u8)) {
+        self.table.rehash_in_place(|slot| hash(&slot.pair().0));
+    }
+
+    fn try_clone(&self) -> Result<Self, MapError>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let alloc = self.table.allocator();
+        let mut clone = Self::with_capacity_in(self.table.capacity(), alloc.clone())?;
+        // 失败时已克隆的槽位由 `clone` 的析构释放
+        self.table
+            .clone_into_with(&mut clone.table, |slot| T::try_from_pair(slot.pair().clone(), alloc))?;
+        Ok(clone)
+    }
+
+    fn clear(&mut self) {
+        self.release_all();
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        TableIter { raw: self.table.raw_iter(), marker: PhantomData }
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        TableIterMut { raw: self.table.raw_iter(), marker: PhantomData }
+    }
+
+    fn into_iter(self) -> Self::IntoIter {
+        // 表的所有权交给迭代器，跳过 `TableStorage` 自身的析构
+        let this = mem::ManuallyDrop::new(self);
+        let table = unsafe { ptr::read(&this.table) };
+        TableIntoIter { raw: RawIntoIter::new(table), marker: PhantomData }
+    }
+
+    fn drain(&mut self) -> Self::Drain<'_> {
+        TableDrain { raw: self.table.drain(), marker: PhantomData }
+    }
+}
+
+/// 槽位内存储的不可变迭代器
+pub struct TableIter<'a, K, V, T> {
+    raw: RawIter<T>,
+    marker: PhantomData<&'a (K, V)>,
+}
+
+impl<K, V, T> Clone for TableIter<'_, K, V, T> {
+    fn clone(&self) -> Self {
+        Self { raw: self.raw.clone(), marker: PhantomData }
+    }
+}
+
+impl<'a, K, V, T: PairSlot<K, V> + 'a> Iterator for TableIter<'a, K, V, T> {
+    type Item = &'a (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.next().map(|slot| unsafe { &*slot.as_ptr() }.pair())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+
+    #[inline]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.raw.fold(init, |acc, slot| f(acc, unsafe { &*slot.as_ptr() }.pair()))
+    }
+}
+
+impl<'a, K, V, T: PairSlot<K, V> + 'a> DoubleEndedIterator for TableIter<'a, K, V, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.raw.next_back().map(|slot| unsafe { &*slot.as_ptr() }.pair())
+    }
+}
+
+impl<'a, K, V, T: PairSlot<K, V> + 'a> ExactSizeIterator for TableIter<'a, K, V, T> {}
+
+impl<'a, K, V, T: PairSlot<K, V> + 'a> FusedIterator for TableIter<'a, K, V, T> {}
+
+/// 槽位内存储的可变迭代器
+pub struct TableIterMut<'a, K, V, T> {
+    raw: RawIter<T>,
+    marker: PhantomData<&'a mut (K, V)>,
+}
+
+impl<'a, K, V, T: PairSlot<K, V> + 'a> Iterator for TableIterMut<'a, K, V, T> {
+    type Item = &'a mut (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.next().map(|slot| unsafe { &mut *slot.as_ptr() }.pair_mut())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+
+    #[inline]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.raw.fold(init, |acc, slot| f(acc, unsafe { &mut *slot.as_ptr() }.pair_mut()))
+    }
+}
+
+impl<'a, K, V, T: PairSlot<K, V> + 'a> DoubleEndedIterator for TableIterMut<'a, K, V, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.raw.next_back().map(|slot| unsafe { &mut *slot.as_ptr() }.pair_mut())
+    }
+}
+
+impl<'a, K, V, T: PairSlot<K, V> + 'a> ExactSizeIterator for TableIterMut<'a, K, V, T> {}
+
+impl<'a, K, V, T: PairSlot<K, V> + 'a> FusedIterator for TableIterMut<'a, K, V, T> {}
+
+/// 槽位内存储的消耗迭代器
+pub struct TableIntoIter<K, V, T: AllocSlot<K, V, A>, A: Allocator> {
+    raw: RawIntoIter<T, A>,
+    marker: PhantomData<(K, V)>,
+}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> Iterator for TableIntoIter<K, V, T, A> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.raw.next()?;
+        Some(unsafe { value.into_pair(self.raw.allocator()) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> DoubleEndedIterator for TableIntoIter<K, V, T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let value = self.raw.next_back()?;
+        Some(unsafe { value.into_pair(self.raw.allocator()) })
+    }
+}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> ExactSizeIterator for TableIntoIter<K, V, T, A> {}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> FusedIterator for TableIntoIter<K, V, T, A> {}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> Drop for TableIntoIter<K, V, T, A> {
+    fn drop(&mut self) {
+        // 剩余的槽位数据可能持有分配器内存，逐个释放
+        if T::NEEDS_RELEASE {
+            self.for_each(drop);
+        }
+    }
+}
+
+/// 槽位内存储的排空迭代器
+pub struct TableDrain<'a, K, V, T: AllocSlot<K, V, A>, A: Allocator> {
+    raw: RawDrain<'a, T, A>,
+    marker: PhantomData<(K, V)>,
+}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> Iterator for TableDrain<'_, K, V, T, A> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.raw.next()?;
+        Some(unsafe { value.into_pair(self.raw.allocator()) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> DoubleEndedIterator for TableDrain<'_, K, V, T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let value = self.raw.next_back()?;
+        Some(unsafe { value.into_pair(self.raw.allocator()) })
+    }
+}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> ExactSizeIterator for TableDrain<'_, K, V, T, A> {}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> FusedIterator for TableDrain<'_, K, V, T, A> {}
+
+impl<K, V, T: AllocSlot<K, V, A>, A: Allocator> Drop for TableDrain<'_, K, V, T, A> {
+    fn drop(&mut self) {
+        // 剩余的槽位数据可能持有分配器内存，逐个释放
+        if T::NEEDS_RELEASE {
+            self.for_each(drop);
+        }
+    }
+}
+
+/// 向量存储：分组表保存下标，键值对连续存放
+pub struct VectorStorage<K, V, A: Allocator> {
+    // 分组索引表：控制字节 + 溢出元数据 + 指向 `values` 的下标
+    table: RawTable<u32, A>,
+    // 连续存放的键值对，长度始终等于 `table.len()`
+    values: DenseArray<(K, V), A>,
+}
+
+impl<K, V, A: Allocator> VectorStorage<K, V, A> {
+    /// 分配分组表，检查下标是否放得进 32 位槽位
+    fn allocate_table(capacity: usize, alloc: A) -> Result<RawTable<u32, A>, MapError> {
+        let table = RawTable::with_capacity_in(capacity, alloc)?;
+        if table.capacity() > MAX_INDEX {
+            return Err(MapError::CapacityExceeded);
+        }
+        Ok(table)
+    }
+
+    /// 以切片形式访问所有键值对
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[(K, V)] {
+        self.values.as_slice()
+    }
+
+    /// 以可变切片形式访问所有键值对（内部使用，调用方不得修改键）
+    #[inline]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [(K, V)] {
+        self.values.as_mut_slice()
+    }
+
+    /// 在空闲槽位写入键值对并追加到稠密数组末尾
+    ///
+    /// 稠密数组按分组表的负载上限分配，写入不会分配内存。
+    #[inline]
+    pub(crate) fn push_at(&mut self, slot: usize, full_hash: u64, fragment: u8, pair: (K, V)) {
+        let index = self.values.push(pair);
+        self.table.insert_at(slot, full_hash, fragment, index as u32);
+        debug_assert_eq!(self.table.len(), self.values.len());
+    }
+
+    /// 查找指向稠密数组下标 `index` 的分组槽位
+    #[inline]
+    pub(crate) fn find_index(&self, full_hash: u64, fragment: u8, index: usize) -> Option<usize> {
+        self.table.find(full_hash, fragment, |&i| i as usize == index)
+    }
+
+    /// 处于 FULL 状态的分组槽位所指向的稠密数组下标
+    #[inline]
+    pub(crate) fn index_at(&self, slot: usize) -> usize {
+        debug_assert_eq!(self.table.slot_state(slot), SlotState::Full);
+        unsafe { *self.table.slot(slot) as usize }
+    }
+
+    /// 从稠密数组移除下标 `index` 处的键值对，并修正被搬移元素的槽位
+    fn remove_value(&mut self, index: usize, hash: impl Fn(&K) -> (u64, u8)) -> (K, V) {
+        let (pair, moved_from) = self.values.swap_remove(index);
+        if let Some(last) = moved_from {
+            // 末尾元素被移到 `index`，找到指向它的槽位并改写下标
+            let (full_hash, fragment) = hash(&self.values.as_slice()[index].0);
+            let slot = self
+                .table
+                .find(full_hash, fragment, |&i| i as usize == last)
+                .expect("moved value must be indexed by the table");
+            unsafe { *self.table.slot_mut(slot) = index as u32 };
+        }
+        pair
+    }
+}
+
+impl<K, V, A: Allocator + Clone> RawStorage<K, V, A> for VectorStorage<K, V, A> {
+    type Iter<'a> = slice::Iter<'a, (K, V)> where Self: 'a, K: 'a, V: 'a;
+    type IterMut<'a> = slice::IterMut<'a, (K, V)> where Self: 'a, K: 'a, V: 'a;
+    type IntoIter = dense_array::IntoIter<(K, V), A>;
+    type Drain<'a> = dense_array::Drain<'a, (K, V), A> where Self: 'a, K: 'a, V: 'a;
+
+    fn new_in(alloc: A) -> Self {
+        Self { table: RawTable::new_in(alloc.clone()), values: DenseArray::new_in(alloc) }
+    }
+
+    fn with_capacity_in(capacity: usize, alloc: A) -> Result<Self, MapError> {
+        let table = Self::allocate_table(capacity, alloc.clone())?;
+        // 稠密数组只需容纳分组表在扩容前能放下的元素
+        let values = DenseArray::with_capacity_in(max_len(table.capacity()), alloc)?;
+        Ok(Self { table, values })
+    }
+
+    #[inline]
+    fn allocator(&self) -> &A {
+        self.table.allocator()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.table.capacity()
+    }
+
+    #[inline]
+    fn group_count(&self) -> usize {
+        self.table.group_count()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    fn deleted(&self) -> usize {
+        self.table.deleted()
+    }
+
+    #[inline]
+    fn needs_grow(&self) -> bool {
+        self.table.needs_grow()
+    }
+
+    #[inline]
+    fn chunk_meta(&self, group_index: usize) -> ChunkMeta {
+        self.table.chunk_meta(group_index)
+    }
+
+    #[inline]
+    fn slot_state(&self, slot: usize) -> SlotState {
+        self.table.slot_state(slot)
+    }
+
+    #[inline]
+    fn find(&self, full_hash: u64, fragment: u8, mut eq: impl FnMut(&K) -> bool) -> Option<usize> {
+        let values = self.values.as_slice();
+        self.table.find(full_hash, fragment, |&index| eq(&values[index as usize].0))
+    }
+
+    #[inline]
+    fn prefetch(&self, full_hash: u64, fragment: u8) {
+        self.table.prefetch(full_hash, fragment)
+    }
+
+    #[inline]
+    fn full_mask(&self, group_index: usize) -> u16 {
+        self.table.full_mask(group_index)
+    }
+
+    #[inline]
+    fn find_insert_slot(&self, full_hash: u64, fragment: u8) -> Option<usize> {
+        self.table.find_insert_slot(full_hash, fragment)
+    }
+
+    #[inline]
+    fn pair(&self, slot: usize) -> &(K, V) {
+        let index = unsafe { *self.table.slot(slot) } as usize;
+        &self.values.as_slice()[index]
+    }
+
+    #[inline]
+    fn pair_mut(&mut self, slot: usize) -> &mut (K, V) {
+        let index = unsafe { *self.table.slot(slot) } as usize;
+        &mut self.values.as_mut_slice()[index]
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    unsafe fn pair_ptr(&self, slot: usize) -> NonNull<(K, V)> {
+        let index = unsafe { *self.table.slot(slot) } as usize;
+        debug_assert!(index < self.values.len());
+        unsafe { self.values.as_ptr().add(index) }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    unsafe fn read_pair(&self, slot: usize) -> (K, V) {
+        unsafe { ptr::read(self.pair_ptr(slot).as_ptr()) }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn forget_all(&mut self) {
+        self.values.forget_all();
+        self.table.clear_no_drop();
+    }
+
+    #[inline]
+    fn insert_at(&mut self, slot: usize, full_hash: u64, fragment: u8, pair: (K, V)) -> Result<(), MapError> {
+        self.push_at(slot, full_hash, fragment, pair);
+        Ok(())
+    }
+
+    fn erase_at(
+        &mut self,
+        slot: usize,
+        full_hash: u64,
+        fragment: u8,
+        hash: impl Fn(&K) -> (u64, u8),
+    ) -> (K, V) {
+        let index = self.table.erase_at(slot, full_hash, fragment) as usize;
+        self.remove_value(index, hash)
+    }
+
+    fn tombstone_at(&mut self, slot: usize, hash: impl Fn(&K) -> (u64, u8)) -> (K, V) {
+        let index = self.table.tombstone_at(slot) as usize;
+        self.remove_value(index, hash)
+    }
+
+    fn rehash(&mut self, capacity: usize, hash: impl Fn(&K) -> (u64, u8)) -> Result<(), MapError> {
+        // 键值对本身不移动，只重新计算每个下标应放入的槽位
+        let mut table = Self::allocate_table(capacity, self.table.allocator().clone())?;
+        for (index, (key, _)) in self.values.as_slice().iter().enumerate() {
+            let (full_hash, fragment) = hash(key);
+            let slot = table
+                .find_insert_slot(full_hash, fragment)
+                .ok_or(MapError::CapacityExceeded)?;
+            table.insert_at(slot, full_hash, fragment, index as u32);
+        }
+        // 容量变化时把键值对搬到新数组（一次连续内存拷贝），与分组表一同增长或收缩
+        let max_len = max_len(table.capacity());
+        if max_len != self.values.capacity() {
+            self.values.reallocate(max_len)?;
+        }
+        self.table = table;
+        Ok(())
+    }
+
+    fn rehash_in_place(&mut self, hash: impl Fn(&K) -> (u64, u8)) {
+        // 键值对不移动，只在分组表内重新放置下标
+        let values = self.values.as_slice();
+        self.table.rehash_in_place(|&index| hash(&values[index as usize].0));
+    }
+
+    fn try_clone(&self) -> Result<Self, MapError>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let values = self.values.try_clone()?;
+        let mut table = Self::allocate_table(self.table.capacity(), self.table.allocator().clone())?;
+        // 下标原样复制，克隆不会失败
+        self.table.clone_into_with(&mut table, |&index| Ok(index))?;
+        Ok(Self { table, values })
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+        self.table.clear_no_drop();
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.values.as_slice().iter()
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.values.as_mut_slice().iter_mut()
+    }
+
+    fn into_iter(self) -> Self::IntoIter {
+        dense_array::IntoIter::new(self.values)
+    }
+
+    fn drain(&mut self) -> Self::Drain<'_> {
+        // 下标不需要析构，先清空分组表
+        self.table.clear_no_drop();
+        self.values.drain()
+    }
+}
+
+/// 键值对小于该字节数时快速策略使用值布局，否则使用向量布局（与 Folly 一致）
+const FAST_INLINE_LIMIT: usize = 24;
+
+/// 快速策略是否选择值布局，对具体的 `K`、`V` 是编译期常量
+#[inline]
+const fn fast_inline<K, V>() -> bool {
+    mem::size_of::<(K, V)>() < FAST_INLINE_LIMIT
+}
+
+/// 快速存储：小键值对内联存放，大键值对使用向量布局
+pub enum FastStorage<K, V, A: Allocator> {
+    Inline(TableStorage<K, V, (K, V), A>),
+    Vector(VectorStorage<K, V, A>),
+}
+
+/// 两种迭代器之一
+#[derive(Clone)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+macro_rules! either {
+    ($value:expr, $inner:pat => $body:expr) => {
+        match $value {
+            Either::Left($inner) => $body,
+            Either::Right($inner) => $body,
+        }
+    };
+}
+
+impl<L, R> Iterator for Either<L, R>
+where
+    L: Iterator,
+    R: Iterator<Item = L::Item>,
+{
+    type Item = L::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        either!(self, iter => iter.next())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        either!(self, iter => iter.size_hint())
+    }
+}
+
+impl<L, R> DoubleEndedIterator for Either<L, R>
+where
+    L: DoubleEndedIterator,
+    R: DoubleEndedIterator<Item = L::Item>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        either!(self, iter => iter.next_back())
+    }
+}
+
+impl<L, R> ExactSizeIterator for Either<L, R>
+where
+    L: ExactSizeIterator,
+    R: ExactSizeIterator<Item = L::Item>,
+{
+}
+
+impl<L, R> FusedIterator for Either<L, R>
+where
+    L: FusedIterator,
+    R: FusedIterator<Item = L::Item>,
+{
+}
+
+macro_rules! fast_dispatch {
+    ($storage:expr, $inner:pat => $body:expr) => {
+        match $storage {
+            FastStorage::Inline($inner) => $body,
+            FastStorage::Vector($inner) => $body,
+        }
+    };
+}
+
+impl<K, V, A: Allocator + Clone> RawStorage<K, V, A> for FastStorage<K, V, A> {
+    type Iter<'a> = Either<TableIter<'a, K, V, (K, V)>, slice::Iter<'a, (K, V)>>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+    type IterMut<'a> = Either<TableIterMut<'a, K, V, (K, V)>, slice::IterMut<'a, (K, V)>>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+    type IntoIter = Either<TableIntoIter<K, V, (K, V), A>, dense_array::IntoIter<(K, V), A>>;
+    type Drain<'a> = Either<TableDrain<'a, K, V, (K, V), A>, dense_array::Drain<'a, (K, V), A>>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    fn new_in(alloc: A) -> Self {
+        if fast_inline::<K, V>() {
+            FastStorage::Inline(TableStorage::new_in(alloc))
+        } else {
+            FastStorage::Vector(VectorStorage::new_in(alloc))
+        }
+    }
+
+    fn with_capacity_in(capacity: usize, alloc: A) -> Result<Self, MapError> {
+        Ok(if fast_inline::<K, V>() {
+            FastStorage::Inline(TableStorage::with_capacity_in(capacity, alloc)?)
+        } else {
+            FastStorage::Vector(VectorStorage::with_capacity_in(capacity, alloc)?)
+        })
+    }
+
+    #[inline]
+    fn allocator(&self) -> &A {
+        fast_dispatch!(self, storage => storage.allocator())
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        fast_dispatch!(self, storage => storage.capacity())
+    }
+
+    #[inline]
+    fn group_count(&self) -> usize {
+        fast_dispatch!(self, storage => storage.group_count())
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        fast_dispatch!(self, storage => storage.len())
+    }
+
+    #[inline]
+    fn deleted(&self) -> usize {
+        fast_dispatch!(self, storage => storage.deleted())
+    }
+
+    #[inline]
+    fn needs_grow(&self) -> bool {
+        fast_dispatch!(self, storage => storage.needs_grow())
+    }
+
+    #[inline]
+    fn chunk_meta(&self, group_index: usize) -> ChunkMeta {
+        fast_dispatch!(self, storage => storage.chunk_meta(group_index))
+    }
+
+    #[inline]
+    fn slot_state(&self, slot: usize) -> SlotState {
+        fast_dispatch!(self, storage => storage.slot_state(slot))
+    }
+
+    #[inline]
+    fn find(&self, full_hash: u64, fragment: u8, eq: impl FnMut(&K) -> bool) -> Option<usize> {
+        fast_dispatch!(self, storage => storage.find(full_hash, fragment, eq))
+    }
+
+    #[inline]
+    fn prefetch(&self, full_hash: u64, fragment: u8) {
+        fast_dispatch!(self, storage => storage.prefetch(full_hash, fragment))
+    }
+
+    #[inline]
+    fn full_mask(&self, group_index: usize) -> u16 {
+        fast_dispatch!(self, storage => storage.full_mask(group_index))
+    }
+
+    #[inline]
+    fn find_insert_slot(&self, full_hash: u64, fragment: u8) -> Option<usize> {
+        fast_dispatch!(self, storage => storage.find_insert_slot(full_hash, fragment))
+    }
+
+    #[inline]
+    fn pair(&self, slot: usize) -> &(K, V) {
+        fast_dispatch!(self, storage => storage.pair(slot))
+    }
+
+    #[inline]
+    fn pair_mut(&mut self, slot: usize) -> &mut (K, V) {
+        fast_dispatch!(self, storage => storage.pair_mut(slot))
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    unsafe fn pair_ptr(&self, slot: usize) -> NonNull<(K, V)> {
+        fast_dispatch!(self, storage => unsafe { storage.pair_ptr(slot) })
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    unsafe fn read_pair(&self, slot: usize) -> (K, V) {
+        fast_dispatch!(self, storage => unsafe { storage.read_pair(slot) })
+    }
+
+    #[cfg(feature = "rayon")]
+    fn forget_all(&mut self) {
+        fast_dispatch!(self, storage => storage.forget_all())
+    }
+
+    #[inline]
+    fn insert_at(&mut self, slot: usize, full_hash: u64, fragment: u8, pair: (K, V)) -> Result<(), MapError> {
+        fast_dispatch!(self, storage => storage.insert_at(slot, full_hash, fragment, pair))
+    }
+
+    fn erase_at(
+        &mut self,
+        slot: usize,
+        full_hash: u64,
+        fragment: u8,
+        hash: impl Fn(&K) -> (u64, u8),
+    ) -> (K, V) {
+        fast_dispatch!(self, storage => storage.erase_at(slot, full_hash, fragment, hash))
+    }
+
+    fn tombstone_at(&mut self, slot: usize, hash: impl Fn(&K) -> (u64, u8)) -> (K, V) {
+        fast_dispatch!(self, storage => storage.tombstone_at(slot, hash))
+    }
+
+    fn rehash(&mut self, capacity: usize, hash: impl Fn(&K) -> (u64, u8)) -> Result<(), MapError> {
+        fast_dispatch!(self, storage => storage.rehash(capacity, hash))
+    }
+
+    fn rehash_in_place(&mut self, hash: impl Fn(&K) -> (u64, u8)) {
+        fast_dispatch!(self, storage => storage.rehash_in_place(hash))
+    }
+
+    fn try_clone(&self) -> Result<Self, MapError>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        Ok(match self {
+            FastStorage::Inline(storage) => FastStorage::Inline(storage.try_clone()?),
+            FastStorage::Vector(storage) => FastStorage::Vector(storage.try_clone()?),
+        })
+    }
+
+    fn clear(&mut self) {
+        fast_dispatch!(self, storage => storage.clear())
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        match self {
+            FastStorage::Inline(storage) => Either::Left(storage.iter()),
+            FastStorage::Vector(storage) => Either::Right(storage.iter()),
+        }
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        match self {
+            FastStorage::Inline(storage) => Either::Left(storage.iter_mut()),
+            FastStorage::Vector(storage) => Either::Right(storage.iter_mut()),
+        }
+    }
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            FastStorage::Inline(storage) => Either::Left(storage.into_iter()),
+            FastStorage::Vector(storage) => Either::Right(storage.into_iter()),
+        }
+    }
+
+    fn drain(&mut self) -> Self::Drain<'_> {
+        match self {
+            FastStorage::Inline(storage) => Either::Left(storage.drain()),
+            FastStorage::Vector(storage) => Either::Right(storage.drain()),
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! 分配失败测试
+//!
+//! 安装一个可以按线程开关的全局分配器：开启后拒绝所有按 64 字节对齐的请求
+//! （分组表、稠密数组以及下面的 `Aligned` 节点），其他分配照常进行。
+
+use f14vectormap::{F14Map, F14NodeMap, F14NodeSet, F14VectorMap, MapError};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    hash::RandomState,
+};
+
+struct FailingAllocator;
+
+thread_local! {
+    static FAIL_ALIGNED: Cell<bool> = const { Cell::new(false) };
+}
+
+unsafe impl GlobalAlloc for FailingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.align() >= 64 && FAIL_ALIGNED.with(Cell::get) {
+            return std::ptr::null_mut();
+        }
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: FailingAllocator = FailingAllocator;
+
+/// 在对齐分配全部失败的情况下执行 `f`
+fn with_failing_alloc<R>(f: impl FnOnce() -> R) -> R {
+    FAIL_ALIGNED.with(|fail| fail.set(true));
+    let result = f();
+    FAIL_ALIGNED.with(|fail| fail.set(false));
+    result
+}
+
+fn assert_alloc_failed<T: std::fmt::Debug>(result: Result<T, MapError>) {
+    match result {
+        Err(MapError::AllocationFailed { layout }) => assert_eq!(layout.align(), 64),
+        other => panic!("expected AllocationFailed, got {:?}", other),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[repr(align(64))]
+struct Aligned(u64);
+
+#[test]
+fn test_constructor_failure() {
+    assert_alloc_failed(with_failing_alloc(|| {
+        F14VectorMap::<u64, u64, RandomState>::with_capacity(100)
+    }));
+    // 不需要分配的构造不受影响
+    let map = with_failing_alloc(F14VectorMap::<u64, u64, RandomState>::new).unwrap();
+    assert_eq!(map.capacity(), 0);
+}
+
+#[test]
+fn test_insert_resize_failure_keeps_map() {
+    let mut map = F14VectorMap::<u64, String, RandomState>::new().unwrap();
+    let mut i = 0;
+    // 填到下一次插入必然扩容
+    while map.len() < map.capacity() * 7 / 10 || map.capacity() == 0 {
+        map.insert(i, i.to_string()).unwrap();
+        i += 1;
+    }
+    let capacity = map.capacity();
+    let len = map.len();
+
+    assert_alloc_failed(with_failing_alloc(|| map.insert(i, i.to_string())));
+    assert_eq!(map.capacity(), capacity);
+    assert_eq!(map.len(), len);
+    assert!(!map.contains_key(&i));
+    for k in 0..i {
+        assert_eq!(map.get(&k), Some(&k.to_string()));
+    }
+
+    // 分配恢复后继续正常工作
+    map.insert(i, i.to_string()).unwrap();
+    assert!(map.capacity() > capacity);
+    assert_eq!(map.len(), len + 1);
+}
+
+#[test]
+fn test_reserve_failure_keeps_map() {
+    let mut map = F14Map::<u64, u64>::with_capacity(16).unwrap();
+    for i in 0..10 {
+        map.insert(i, i).unwrap();
+    }
+    assert_alloc_failed(with_failing_alloc(|| map.try_reserve(1000)));
+    assert_eq!(map.capacity(), 16);
+    assert_eq!(map.iter().count(), 10);
+
+    // 缩容失败时保留原表
+    map.reserve(1000);
+    let capacity = map.capacity();
+    with_failing_alloc(|| map.shrink_to_fit());
+    assert_eq!(map.capacity(), capacity);
+    assert!((0..10).all(|i| map.get(&i) == Some(&i)));
+}
+
+#[test]
+fn test_rebuild_needs_no_allocation() {
+    use f14vectormap::f14_map::SlotState;
+
+    let mut map = F14VectorMap::<u64, u64, RandomState>::with_capacity(64).unwrap();
+    for i in 0..20 {
+        map.insert(i, i).unwrap();
+    }
+    let full: Vec<usize> = (0..map.capacity())
+        .filter(|&slot| map.slot_state(slot) == SlotState::Full)
+        .take(5)
+        .collect();
+    for slot in full {
+        unsafe { map.replace_slot_state(slot, SlotState::Deleted) };
+    }
+    assert_eq!(map.deleted_count(), 5);
+
+    // 原地重建不分配内存，分配全部失败时也能完成
+    let capacity = map.capacity();
+    assert_eq!(with_failing_alloc(|| map.rebuild()), Ok(()));
+    assert_eq!(map.deleted_count(), 0);
+    assert_eq!(map.len(), 15);
+    assert_eq!(map.capacity(), capacity);
+    assert_eq!(map.iter().count(), 15);
+
+    with_failing_alloc(|| map.compact());
+    assert_eq!(map.len(), 15);
+    for (key, value) in map.iter() {
+        assert_eq!(map.get(key), Some(value));
+    }
+}
+
+#[test]
+fn test_node_allocation_failure() {
+    let mut map = F14NodeMap::<u64, Aligned, RandomState>::new().unwrap();
+    map.insert(1, Aligned(1)).unwrap();
+    map.reserve(10);
+
+    // 表空间已预留，失败的只有节点分配
+    assert_alloc_failed(with_failing_alloc(|| map.insert(2, Aligned(2))));
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get(&2), None);
+    assert_eq!(map.get(&1), Some(&Aligned(1)));
+
+    // 覆盖已有键不需要分配
+    assert_eq!(with_failing_alloc(|| map.insert(1, Aligned(10))), Ok(Some(Aligned(1))));
+
+    map.insert(2, Aligned(2)).unwrap();
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn test_node_set_replace_failure() {
+    let mut set = F14NodeSet::<Aligned, RandomState>::new().unwrap();
+    set.insert(Aligned(1)).unwrap();
+    set.reserve(10);
+
+    // 新元素的节点分配失败时返回错误，集合保持不变
+    assert_alloc_failed(with_failing_alloc(|| set.replace(Aligned(2))));
+    assert_eq!(set.len(), 1);
+    assert!(!set.contains(&Aligned(2)));
+
+    // 替换已有元素不需要分配
+    assert_eq!(with_failing_alloc(|| set.replace(Aligned(1))), Ok(Some(Aligned(1))));
+
+    assert_eq!(set.replace(Aligned(2)), Ok(None));
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn test_batch_failure_keeps_map() {
+    let mut map = F14VectorMap::<u64, u64, RandomState>::new().unwrap();
+    map.apply_batch((0..10).map(|i| (i, Some(i)))).unwrap();
+    let capacity = map.capacity();
+
+    // 预留空间失败时不应用任何更新
+    let batch: Vec<(u64, Option<u64>)> = (0..10)
+        .map(|i| (i, None))
+        .chain((10..1000).map(|i| (i, Some(i))))
+        .collect();
+    assert_alloc_failed(with_failing_alloc(|| map.apply_batch(batch)));
+    assert_eq!(map.batch_version(), 1);
+    assert_eq!(map.capacity(), capacity);
+    assert_eq!(map.len(), 10);
+    assert!((0..10).all(|i| map.get(&i) == Some(&i)));
+
+    let report = map.apply_batch((0..10).map(|i| (i, None))).unwrap();
+    assert_eq!(report.removed, 10);
+    assert_eq!(report.batch_version, 2);
+}
+
+#[test]
+fn test_clone_failure_keeps_source() {
+    let mut map = F14VectorMap::<u64, String, RandomState>::new().unwrap();
+    map.extend((0..100).map(|i| (i, i.to_string())));
+
+    assert_alloc_failed(with_failing_alloc(|| map.try_clone()));
+    assert_eq!(map.len(), 100);
+
+    let mut nodes = F14NodeMap::<u64, Aligned, RandomState>::new().unwrap();
+    nodes.extend((0..10).map(|i| (i, Aligned(i))));
+    assert_alloc_failed(with_failing_alloc(|| nodes.try_clone()));
+    assert_eq!(nodes.len(), 10);
+    assert_eq!(nodes.get(&3), Some(&Aligned(3)));
+}
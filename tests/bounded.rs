@@ -0,0 +1,155 @@
+//! 有界映射驱逐策略测试
+
+use f14vectormap::{bounded::Inserted, BoundedF14Map, EvictionPolicy, MapError};
+use std::collections::hash_map::RandomState;
+
+type Bounded<K, V> = BoundedF14Map<K, V, RandomState>;
+
+fn evicted_keys<K: Copy, V>(inserted: &Inserted<K, V>) -> Vec<K> {
+    inserted.evicted.iter().map(|(key, _)| *key).collect()
+}
+
+#[test]
+fn test_bounded_entry_limit() {
+    for policy in [EvictionPolicy::Clock, EvictionPolicy::Lru, EvictionPolicy::Lfu] {
+        let mut map = Bounded::<u32, String>::new(100, policy).unwrap();
+        let mut evicted = 0;
+        for i in 0..1000 {
+            let inserted = map.insert(i, i.to_string()).unwrap();
+            assert_eq!(inserted.previous, None);
+            for (key, value) in &inserted.evicted {
+                assert_eq!(*value, key.to_string());
+                assert!(!map.contains_key(key));
+            }
+            evicted += inserted.evicted.len();
+            // 刚插入的键不会被立即驱逐
+            assert_eq!(map.peek(&i), Some(&i.to_string()));
+            assert!(map.len() <= 100);
+        }
+        assert_eq!(map.len(), 100);
+        assert_eq!(map.weight(), 100);
+        assert_eq!(evicted, 900);
+
+        // 覆盖已有键不驱逐任何条目
+        let key = *map.iter().next().unwrap().0;
+        let inserted = map.insert(key, "updated".to_string()).unwrap();
+        assert_eq!(inserted.previous, Some(key.to_string()));
+        assert!(inserted.evicted.is_empty());
+
+        assert_eq!(map.remove(&key), Some("updated".to_string()));
+        assert_eq!(map.weight(), 99);
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.weight(), 0);
+    }
+}
+
+#[test]
+fn test_bounded_keeps_hot_entries() {
+    // 时钟：被访问过的条目获得第二次机会
+    let mut clock = Bounded::<u32, u32>::new(4, EvictionPolicy::Clock).unwrap();
+    for i in 0..4 {
+        clock.insert(i, i).unwrap();
+    }
+    assert_eq!(clock.get(&0), Some(&0));
+    assert_eq!(evicted_keys(&clock.insert(4, 4).unwrap()), [1]);
+    assert!(clock.contains_key(&0));
+
+    // LRU：驱逐最久未访问的条目
+    let mut lru = Bounded::<u32, u32>::new(4, EvictionPolicy::Lru).unwrap();
+    for i in 0..4 {
+        lru.insert(i, i).unwrap();
+    }
+    lru.get(&0);
+    lru.get(&1);
+    assert_eq!(evicted_keys(&lru.insert(4, 4).unwrap()), [2]);
+    assert_eq!(evicted_keys(&lru.insert(5, 5).unwrap()), [3]);
+    // peek 不更新访问时间
+    assert_eq!(lru.peek(&0), Some(&0));
+    assert_eq!(evicted_keys(&lru.insert(6, 6).unwrap()), [0]);
+
+    // LFU：驱逐访问次数最少的条目
+    let mut lfu = Bounded::<u32, u32>::new(4, EvictionPolicy::Lfu).unwrap();
+    for i in 0..4 {
+        lfu.insert(i, i).unwrap();
+        for _ in 0..i {
+            lfu.get(&i);
+        }
+    }
+    assert_eq!(evicted_keys(&lfu.insert(4, 4).unwrap()), [0]);
+    assert!(lfu.contains_key(&3));
+}
+
+#[test]
+fn test_bounded_hot_set_survives_sampling() {
+    // 条目数远大于抽样数时，持续访问的热点仍应留在映射中
+    for policy in [EvictionPolicy::Clock, EvictionPolicy::Lru, EvictionPolicy::Lfu] {
+        let mut map = Bounded::<u32, u32>::new(256, policy).unwrap();
+        let hot: Vec<u32> = (0..16).collect();
+        for &key in &hot {
+            map.insert(key, key).unwrap();
+        }
+        for i in 1000..20000 {
+            map.insert(i, i).unwrap();
+            for &key in &hot {
+                map.get(&key);
+            }
+        }
+        let survivors = hot.iter().filter(|key| map.contains_key(*key)).count();
+        assert!(survivors >= 14, "{policy:?}: only {survivors} hot keys survived");
+    }
+}
+
+#[test]
+fn test_bounded_byte_budget() {
+    let mut map =
+        Bounded::<u32, Vec<u8>>::with_byte_budget(1000, EvictionPolicy::Lru, |_, value| 4 + value.len()).unwrap();
+    // 条目数不超过抽样数，LRU 的驱逐顺序是精确的
+    for i in 0..5 {
+        assert!(map.insert(i, vec![0; 196]).unwrap().evicted.is_empty());
+    }
+    assert_eq!(map.weight(), 1000);
+
+    // 一个大条目需要驱逐多个小条目
+    let inserted = map.insert(100, vec![0; 396]).unwrap();
+    assert_eq!(evicted_keys(&inserted), [0, 1]);
+    assert_eq!(map.weight(), 1000);
+    assert_eq!(map.len(), 4);
+
+    // 覆盖时按新旧权重之差计算
+    let inserted = map.insert(100, vec![0; 96]).unwrap();
+    assert_eq!(inserted.previous.map(|value| value.len()), Some(396));
+    assert_eq!(map.weight(), 700);
+
+    // 单个条目超出预算时拒绝写入，映射保持不变
+    assert_eq!(map.insert(200, vec![0; 1000]), Err(MapError::CapacityExceeded));
+    assert_eq!(map.len(), 4);
+    assert_eq!(map.weight(), 700);
+}
+
+#[test]
+fn test_bounded_weight_charged_at_insert() {
+    for policy in [EvictionPolicy::Clock, EvictionPolicy::Lru, EvictionPolicy::Lfu] {
+        let mut map =
+            Bounded::<u32, Vec<u8>>::with_byte_budget(100, policy, |_, value| value.len()).unwrap();
+        map.insert(1, vec![0; 10]).unwrap();
+        map.insert(2, vec![0; 20]).unwrap();
+        assert_eq!(map.weight(), 30);
+
+        // 原地改变大小不影响记账，移除和覆盖扣除写入时计入的权重
+        map.get_mut(&1).unwrap().resize(50, 0);
+        map.get_mut(&2).unwrap().clear();
+        assert_eq!(map.weight(), 30);
+        assert_eq!(map.remove(&1).map(|value| value.len()), Some(50));
+        assert_eq!(map.weight(), 20);
+        map.insert(2, vec![0; 5]).unwrap();
+        assert_eq!(map.weight(), 5);
+
+        // 驱逐同样扣除记录的权重
+        map.get_mut(&2).unwrap().resize(90, 0);
+        let inserted = map.insert(3, vec![0; 100]).unwrap();
+        assert_eq!(evicted_keys(&inserted), [2]);
+        assert_eq!(map.weight(), 100);
+        assert_eq!(map.len(), 1);
+    }
+}
@@ -0,0 +1,136 @@
+//! 并发分片映射测试（`cargo test --features concurrent`）
+
+#![cfg(feature = "concurrent")]
+
+use f14vectormap::{ConcurrentF14Map, MapError, entry::Entry};
+use std::{collections::hash_map::RandomState, thread};
+
+#[test]
+fn test_concurrent_basic_operations() {
+    let map = ConcurrentF14Map::<u64, String, RandomState>::new().unwrap();
+    assert!(map.shard_count().is_power_of_two());
+    assert!(map.is_empty());
+
+    assert_eq!(map.insert(1, "one".to_string()), Ok(None));
+    assert_eq!(map.insert(1, "uno".to_string()), Ok(Some("one".to_string())));
+    assert_eq!(map.get(&1), Some("uno".to_string()));
+    assert_eq!(map.get_with(&1, |value| value.len()), Some(3));
+    assert!(map.contains_key(&1));
+    assert_eq!(map.remove(&1), Ok(Some("uno".to_string())));
+    assert_eq!(map.remove(&1), Ok(None));
+    assert_eq!(map.get(&1), None);
+
+    map.entry(2, |entry| *entry.or_insert_with(String::new) += "a").unwrap();
+    map.entry(2, |entry| *entry.or_insert_with(String::new) += "b").unwrap();
+    assert_eq!(map.get(&2), Some("ab".to_string()));
+
+    // compute 返回旧值，返回 None 时移除
+    assert_eq!(map.compute(3, |_, old| old.map_or(Some("x".into()), |_| None)), Ok(None));
+    assert_eq!(map.compute(3, |_, old| old.map(|v| format!("{v}y"))), Ok(Some("x".to_string())));
+    assert_eq!(map.get(&3), Some("xy".to_string()));
+    assert_eq!(map.compute(3, |_, _| None), Ok(Some("xy".to_string())));
+    assert!(!map.contains_key(&3));
+
+    map.clear().unwrap();
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_concurrent_shard_count() {
+    let map = ConcurrentF14Map::<u64, u64, RandomState>::with_shards_and_hasher(5, 100, RandomState::new())
+        .unwrap();
+    assert_eq!(map.shard_count(), 8);
+
+    let single = ConcurrentF14Map::<u64, u64, RandomState>::with_shards_and_hasher(1, 0, RandomState::new())
+        .unwrap();
+    for i in 0..100 {
+        single.insert(i, i).unwrap();
+    }
+    assert_eq!(single.len(), 100);
+}
+
+#[test]
+fn test_concurrent_writers() {
+    const THREADS: u64 = 8;
+    const PER_THREAD: u64 = 2000;
+
+    let map = ConcurrentF14Map::<u64, u64, RandomState>::new().unwrap();
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let map = &map;
+            scope.spawn(move || {
+                for i in 0..PER_THREAD {
+                    let key = t * PER_THREAD + i;
+                    map.insert(key, key * 2).unwrap();
+                    // 所有线程同时累加同一组计数器
+                    map.compute(i % 16 + 1_000_000, |_, old| Some(old.copied().unwrap_or(0) + 1))
+                        .unwrap();
+                }
+                for i in (0..PER_THREAD).step_by(2) {
+                    let key = t * PER_THREAD + i;
+                    assert_eq!(map.remove(&key), Ok(Some(key * 2)));
+                }
+            });
+        }
+    });
+
+    assert_eq!(map.len() as u64, THREADS * PER_THREAD / 2 + 16);
+    for key in 0..THREADS * PER_THREAD {
+        assert_eq!(map.get(&key), (key % 2 == 1).then_some(key * 2));
+    }
+    let counted: u64 = (0..16).map(|i| map.get(&(1_000_000 + i)).unwrap()).sum();
+    assert_eq!(counted, THREADS * PER_THREAD);
+}
+
+#[test]
+fn test_concurrent_snapshot() {
+    let map = ConcurrentF14Map::<u64, u64, RandomState>::new().unwrap();
+    for i in 0..1000 {
+        map.insert(i, i).unwrap();
+    }
+
+    // 写入者不断增删额外的键并原地改写已有键，快照中原有的键始终完整
+    thread::scope(|scope| {
+        let map = &map;
+        scope.spawn(move || {
+            for _ in 0..200 {
+                let mut snapshot: Vec<(u64, u64)> = map.snapshot().filter(|&(k, _)| k < 1000).collect();
+                snapshot.sort_unstable();
+                assert_eq!(snapshot, (0..1000).map(|i| (i, i)).collect::<Vec<_>>());
+            }
+        });
+        scope.spawn(move || {
+            for round in 0..2000u64 {
+                map.insert(1000 + round, 0).unwrap();
+                map.compute(round % 1000, |_, old| old.copied()).unwrap();
+                if round % 2 == 0 {
+                    map.remove(&(1000 + round)).unwrap();
+                }
+            }
+        });
+    });
+
+    let snapshot = map.snapshot();
+    assert_eq!(snapshot.len(), 2000);
+}
+
+#[test]
+fn test_poisoned_shard() {
+    let map = ConcurrentF14Map::<u64, u64, RandomState>::with_shards_and_hasher(1, 0, RandomState::new())
+        .unwrap();
+    map.insert(1, 1).unwrap();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        map.entry(1, |entry| {
+            if let Entry::Occupied(_) = entry {
+                panic!("writer failed while holding the shard lock");
+            }
+        })
+    }));
+    assert!(result.is_err());
+
+    // 写操作报告并发修改，读操作仍然可用
+    assert_eq!(map.insert(2, 2), Err(MapError::ConcurrentModification));
+    assert_eq!(map.get(&1), Some(1));
+    assert_eq!(map.snapshot().count(), 1);
+}
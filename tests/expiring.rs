@@ -0,0 +1,123 @@
+//! 条目过期测试，使用手动时钟推进时间
+
+use f14vectormap::{expiring::ManualClock, ExpiringF14Map};
+use std::{collections::hash_map::RandomState, sync::Arc, time::Duration};
+
+type Expiring<K, V> = ExpiringF14Map<K, V, RandomState, Arc<ManualClock>>;
+
+fn new_map<K, V>() -> (Expiring<K, V>, Arc<ManualClock>) {
+    let clock = Arc::new(ManualClock::new());
+    (Expiring::with_clock(clock.clone()).unwrap(), clock)
+}
+
+#[test]
+fn test_expired_entries_are_absent() {
+    let (mut map, clock) = new_map::<u32, String>();
+    map.insert_with_ttl(1, "short".to_string(), Duration::from_secs(1)).unwrap();
+    map.insert_with_ttl(2, "long".to_string(), Duration::from_secs(10)).unwrap();
+    map.insert_persistent(3, "forever".to_string()).unwrap();
+
+    assert_eq!(map.get(&1).map(String::as_str), Some("short"));
+    assert_eq!(map.ttl(&1), Some(Some(Duration::from_secs(1))));
+    assert_eq!(map.ttl(&3), Some(None));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(map.get(&1), None);
+    assert!(!map.contains_key(&1));
+    assert_eq!(map.ttl(&1), None);
+    assert_eq!(map.get(&2).map(String::as_str), Some("long"));
+    // 过期条目尚未回收，但迭代时跳过
+    assert_eq!(map.len(), 3);
+    let mut live: Vec<u32> = map.iter().map(|(key, _)| *key).collect();
+    live.sort_unstable();
+    assert_eq!(live, [2, 3]);
+
+    // 覆盖过期条目时不返回旧值，并使用新的截止时间
+    assert_eq!(map.insert_with_ttl(1, "again".to_string(), Duration::from_secs(5)).unwrap(), None);
+    assert_eq!(map.get(&1).map(String::as_str), Some("again"));
+    assert_eq!(map.insert_persistent(1, "kept".to_string()).unwrap(), Some("again".to_string()));
+
+    clock.advance(Duration::from_secs(100));
+    assert_eq!(map.remove(&2), None);
+    assert_eq!(map.get(&1).map(String::as_str), Some("kept"));
+    assert_eq!(map.get_mut(&3).map(|value| value.len()), Some(7));
+}
+
+#[test]
+fn test_default_ttl() {
+    let (mut map, clock) = new_map::<u32, u32>();
+    map.insert(0, 0).unwrap();
+    map.set_default_ttl(Some(Duration::from_millis(500)));
+    assert_eq!(map.default_ttl(), Some(Duration::from_millis(500)));
+    map.insert(1, 1).unwrap();
+
+    clock.advance(Duration::from_millis(499));
+    assert!(map.contains_key(&1));
+    clock.advance(Duration::from_millis(1));
+    assert!(!map.contains_key(&1));
+    // 设置默认存活时间之前插入的条目不受影响
+    assert!(map.contains_key(&0));
+}
+
+#[test]
+fn test_ttl_overflow_never_expires() {
+    let (mut map, clock) = new_map::<u32, u32>();
+    map.insert_with_ttl(1, 1, Duration::MAX).unwrap();
+    map.set_default_ttl(Some(Duration::MAX));
+    map.insert(2, 2).unwrap();
+
+    // 截止时间无法表示，按永不过期处理
+    clock.advance(Duration::from_secs(u64::from(u32::MAX)));
+    assert_eq!(map.ttl(&1), Some(None));
+    assert_eq!(map.ttl(&2), Some(None));
+    assert_eq!(map.get(&1), Some(&1));
+    assert_eq!(map.get(&2), Some(&2));
+}
+
+#[test]
+fn test_expire_step_is_incremental() {
+    let (mut map, clock) = new_map::<u32, u32>();
+    for i in 0..1000 {
+        if i % 4 == 0 {
+            map.insert_persistent(i, i).unwrap();
+        } else {
+            map.insert_with_ttl(i, i, Duration::from_secs(u64::from(i % 3) + 1)).unwrap();
+        }
+    }
+    assert_eq!(map.expire_step(100), 0);
+
+    clock.advance(Duration::from_secs(3));
+    // 每次最多检查 2 个分组（32 个条目）
+    let mut reclaimed = 0;
+    let mut steps = 0;
+    while map.len() > 250 {
+        let step = map.expire_step(2);
+        assert!(step <= 32);
+        reclaimed += step;
+        steps += 1;
+        assert!(steps < 100, "expire_step made no progress");
+    }
+    assert_eq!(reclaimed, 750);
+    assert!(steps >= 750 / 32);
+    assert_eq!(map.expire_step(100), 0);
+
+    for i in 0..1000 {
+        assert_eq!(map.get(&i).is_some(), i % 4 == 0);
+    }
+}
+
+#[test]
+fn test_expire_all() {
+    let (mut map, clock) = new_map::<u32, Vec<u32>>();
+    for i in 0..200 {
+        map.insert_with_ttl(i, vec![i], Duration::from_secs(u64::from(i % 2) + 1)).unwrap();
+    }
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(map.expire_all(), 100);
+    assert_eq!(map.len(), 100);
+    assert!(map.iter().all(|(key, value)| key % 2 == 1 && value == &vec![*key]));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(map.expire_all(), 100);
+    assert!(map.is_empty());
+}
@@ -0,0 +1,141 @@
+//! 渐进式扩容测试
+
+use f14vectormap::{
+    incremental::MIGRATE_CHUNKS,
+    simd_utils::CHUNK_SIZE,
+    IncrementalF14Map,
+};
+use std::collections::hash_map::RandomState;
+
+type Incremental<K, V> = IncrementalF14Map<K, V, RandomState>;
+
+const STEP: usize = MIGRATE_CHUNKS * CHUNK_SIZE;
+
+#[test]
+fn test_incremental_resize_is_bounded() {
+    let mut map = Incremental::<u64, u64>::new().unwrap();
+    let mut resizes = 0;
+    let mut was_resizing = false;
+
+    for i in 0..100_000u64 {
+        let pending = map.pending_migration();
+        assert_eq!(map.insert(i, i * 2).unwrap(), None);
+        if map.is_resizing() {
+            if !was_resizing {
+                resizes += 1;
+            } else {
+                // 每次插入最多迁移固定数量的元素
+                assert!(pending - map.pending_migration() <= STEP);
+            }
+        }
+        was_resizing = map.is_resizing();
+        assert_eq!(map.len() as u64, i + 1);
+    }
+    assert!(resizes >= 10, "only {resizes} resizes");
+
+    for i in 0..100_000u64 {
+        assert_eq!(map.get(&i), Some(&(i * 2)));
+    }
+    assert_eq!(map.iter().count(), 100_000);
+}
+
+#[test]
+fn test_operations_during_migration() {
+    let mut map = Incremental::<u64, String>::new().unwrap();
+    let mut i = 0u64;
+    // 插入到正好开始一次较大的迁移
+    while !(map.is_resizing() && map.pending_migration() > 1000) {
+        map.insert(i, i.to_string()).unwrap();
+        i += 1;
+    }
+    let total = i;
+    assert_eq!(map.len() as u64, total);
+
+    // 迁移期间两张表中的键都能查到、更新和删除
+    for key in 0..total {
+        assert_eq!(map.get(&key), Some(&key.to_string()));
+    }
+    for key in (0..total).step_by(3) {
+        assert_eq!(map.insert(key, format!("updated {key}")).unwrap(), Some(key.to_string()));
+    }
+    for key in (1..total).step_by(3) {
+        assert_eq!(map.remove(&key), Some(key.to_string()));
+    }
+    assert_eq!(map.remove(&total), None);
+    if let Some(value) = map.get_mut(&2) {
+        value.push('!');
+    }
+
+    map.finish_resize();
+    assert!(!map.is_resizing());
+    assert_eq!(map.pending_migration(), 0);
+
+    for key in 0..total {
+        let expected = match key % 3 {
+            0 => Some(format!("updated {key}")),
+            1 => None,
+            _ if key == 2 => Some("2!".to_string()),
+            _ => Some(key.to_string()),
+        };
+        assert_eq!(map.get(&key), expected.as_ref());
+    }
+    assert_eq!(map.len(), map.iter().count());
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.get(&0), None);
+}
+
+#[test]
+fn test_migrate_step_for_readers() {
+    let mut map = Incremental::<u64, u64>::new().unwrap();
+    let mut i = 0u64;
+    while !(map.is_resizing() && map.pending_migration() > 4 * STEP) {
+        map.insert(i, i).unwrap();
+        i += 1;
+    }
+
+    // 只读查找不推进迁移
+    let pending = map.pending_migration();
+    for key in 0..i {
+        assert_eq!(map.get(&key), Some(&key));
+    }
+    assert_eq!(map.pending_migration(), pending);
+
+    assert!(!map.migrate_step());
+    assert_eq!(map.pending_migration(), pending - STEP);
+    while !map.migrate_step() {}
+    assert!(!map.is_resizing());
+    for key in 0..i {
+        assert_eq!(map.get(&key), Some(&key));
+    }
+}
+
+#[test]
+fn test_overwrite_at_load_limit_does_not_grow() {
+    let mut map = Incremental::<u64, u64>::new().unwrap();
+    let mut i = 0u64;
+    // 插入到新表正好达到负载上限（容量的 7/10），且没有进行中的迁移
+    while map.capacity() < 1024 || map.is_resizing() || map.len() < map.capacity() * 7 / 10 {
+        map.insert(i, i).unwrap();
+        i += 1;
+    }
+    let capacity = map.capacity();
+
+    // 覆盖已有的键原地更新，不扩容也不开始迁移
+    for key in 0..i {
+        assert_eq!(map.insert(key, key + 1).unwrap(), Some(key));
+        assert_eq!(map.capacity(), capacity);
+        assert!(!map.is_resizing());
+    }
+    assert_eq!(map.len() as u64, i);
+
+    // 只有真正的新键才开始下一轮渐进扩容
+    assert_eq!(map.insert(i, i + 1).unwrap(), None);
+    assert!(map.is_resizing());
+    assert_eq!(map.capacity(), capacity * 2);
+    map.finish_resize();
+    for key in 0..=i {
+        assert_eq!(map.get(&key), Some(&(key + 1)));
+    }
+}
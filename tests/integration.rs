@@ -278,4 +278,1148 @@ fn test_remove_performance() {
     
     println!("移除1000个元素耗时: {:?}", duration);
     assert!(duration < std::time::Duration::from_millis(1));
-}
\ No newline at end of file
+}
+#[test]
+fn test_entry_api() {
+    use f14vectormap::entry::Entry;
+
+    let mut map = F14VectorMap::<&str, i32, RandomState>::new().unwrap();
+
+    // 计数器累加：键不存在时插入，存在时原地修改
+    for word in ["a", "b", "a", "c", "a", "b"] {
+        *map.entry(word).or_insert(0) += 1;
+    }
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get("a"), Some(&3));
+    assert_eq!(map.get("b"), Some(&2));
+    assert_eq!(map.get("c"), Some(&1));
+
+    // and_modify + or_default
+    map.entry("a").and_modify(|v| *v *= 10).or_default();
+    map.entry("d").and_modify(|v| *v *= 10).or_default();
+    assert_eq!(map.get("a"), Some(&30));
+    assert_eq!(map.get("d"), Some(&0));
+
+    // or_insert_with 只在键不存在时调用
+    map.entry("a").or_insert_with(|| panic!("不应调用"));
+    assert_eq!(*map.entry("e").or_insert_with_key(|k| k.len() as i32), 1);
+
+    // 已占用条目：替换与移除
+    match map.entry("b") {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.key(), &"b");
+            assert_eq!(entry.insert(20), 2);
+            assert_eq!(entry.remove_entry(), ("b", 20));
+        }
+        Entry::Vacant(_) => panic!("键 b 应该存在"),
+    }
+    assert_eq!(map.get("b"), None);
+
+    // 空条目：取回键或插入
+    match map.entry("z") {
+        Entry::Vacant(entry) => {
+            assert_eq!(entry.key(), &"z");
+            *entry.insert(5) += 1;
+        }
+        Entry::Occupied(_) => panic!("键 z 不应存在"),
+    }
+    assert_eq!(map.get("z"), Some(&6));
+    assert_eq!(map.len(), 5);
+}
+
+#[test]
+fn test_entry_growth() {
+    let mut map = F14VectorMap::<String, usize, RandomState>::new().unwrap();
+
+    // 通过条目插入触发多次扩容
+    for i in 0..200 {
+        let entry = map.try_entry(format!("key{}", i)).unwrap();
+        assert_eq!(*entry.or_insert(i), i);
+    }
+    assert_eq!(map.len(), 200);
+    assert!(map.capacity() * 7 / 10 >= 200);
+
+    // 移除后重新插入，不应产生重复键
+    for i in 0..100 {
+        assert_eq!(map.entry(format!("key{}", i)).insert_entry(i + 1).remove(), i + 1);
+    }
+    for i in 0..200 {
+        *map.entry(format!("key{}", i)).or_default() += 1;
+    }
+    assert_eq!(map.len(), 200);
+    for i in 0..100 {
+        assert_eq!(map.get(&format!("key{}", i)), Some(&1));
+    }
+    for i in 100..200 {
+        assert_eq!(map.get(&format!("key{}", i)), Some(&(i + 1)));
+    }
+}
+
+#[test]
+fn test_lookup_family() {
+    let mut map = F14VectorMap::<String, Vec<f32>, RandomState>::new().unwrap();
+    for i in 0..50 {
+        map.insert(format!("item{}", i), vec![i as f32; 4]).unwrap();
+    }
+
+    // Borrow<str> 查找
+    assert!(map.contains_key("item7"));
+    assert!(!map.contains_key("item50"));
+    let (key, value) = map.get_key_value("item7").unwrap();
+    assert_eq!(key, "item7");
+    assert_eq!(value, &vec![7.0; 4]);
+
+    // 原地更新
+    map.get_mut("item7").unwrap()[0] = -1.0;
+    if let Some((key, value)) = map.get_key_value_mut("item8") {
+        assert_eq!(key, "item8");
+        value.push(8.5);
+    }
+    assert_eq!(map.get("item7").unwrap()[0], -1.0);
+    assert_eq!(map.get("item8").unwrap().len(), 5);
+    assert!(map.get_mut("missing").is_none());
+
+    // 移除时取回键
+    assert_eq!(map.remove_entry("item9"), Some(("item9".to_string(), vec![9.0; 4])));
+    assert_eq!(map.remove_entry("item9"), None);
+    assert!(!map.contains_key("item9"));
+    assert_eq!(map.len(), 49);
+}
+
+#[test]
+fn test_remove_drops_key() {
+    use std::rc::Rc;
+
+    let mut map = F14VectorMap::<Rc<i32>, i32, RandomState>::new().unwrap();
+    let keys: Vec<Rc<i32>> = (0..10).map(Rc::new).collect();
+    for key in &keys {
+        map.insert(key.clone(), **key).unwrap();
+    }
+    assert!(keys.iter().all(|key| Rc::strong_count(key) == 2));
+
+    // remove 必须释放映射持有的键
+    for key in &keys[..5] {
+        assert_eq!(map.remove(key), Some(**key));
+        assert_eq!(Rc::strong_count(key), 1);
+    }
+
+    // remove_entry 将键的所有权交还给调用者
+    let (owned, value) = map.remove_entry(&keys[5]).unwrap();
+    assert_eq!(value, 5);
+    assert_eq!(Rc::strong_count(&owned), 2);
+    drop(owned);
+    assert_eq!(Rc::strong_count(&keys[5]), 1);
+
+    drop(map);
+    assert!(keys.iter().all(|key| Rc::strong_count(key) == 1));
+}
+
+#[test]
+fn test_overflow_metadata() {
+    // 所有键的起始组都是 0，探测步长为 1
+    let mut map = F14VectorMap::with_hasher(FixedHasher).unwrap();
+    for i in 0..40 {
+        map.insert(i, i).unwrap();
+    }
+    assert_eq!(map.group_count(), 4);
+
+    // 组 0、1 填满，剩余 8 个键落在组 2
+    assert_eq!(map.chunk_meta(0).outbound_overflow(), 24);
+    assert_eq!(map.chunk_meta(1).outbound_overflow(), 8);
+    assert_eq!(map.chunk_meta(2).outbound_overflow(), 0);
+    assert_eq!(map.chunk_meta(0).hosted_overflow(), 0);
+    assert_eq!(map.chunk_meta(1).hosted_overflow(), 16);
+    assert_eq!(map.chunk_meta(2).hosted_overflow(), 8);
+
+    for i in 0..40 {
+        assert_eq!(map.get(&i), Some(&i));
+    }
+    for i in 40..80 {
+        assert_eq!(map.get(&i), None);
+    }
+}
+
+#[test]
+fn test_negative_lookups() {
+    let mut map = F14VectorMap::<u64, u64, RandomState>::with_capacity(100).unwrap();
+    // 分组数向上取整为 2 的幂
+    assert_eq!(map.capacity(), 128);
+    assert!(map.group_count().is_power_of_two());
+
+    for i in 0..5000 {
+        map.insert(i, i * 2).unwrap();
+    }
+    for i in 0..5000 {
+        assert_eq!(map.get(&i), Some(&(i * 2)));
+    }
+    for i in 5000..10000 {
+        assert!(!map.contains_key(&i));
+    }
+
+    // 托管计数之和等于不在起始组的键数量，不可能超过元素总数
+    let hosted: usize = (0..map.group_count())
+        .map(|g| map.chunk_meta(g).hosted_overflow() as usize)
+        .sum();
+    assert!(hosted <= map.len());
+
+    map.clear();
+    assert!((0..map.group_count()).all(|g| map.chunk_meta(g) == Default::default()));
+}
+
+#[test]
+fn test_churn_without_tombstones() {
+    let mut map = F14VectorMap::<u64, u64, RandomState>::with_capacity(1024).unwrap();
+    let capacity = map.capacity();
+
+    // 长时间插入/删除交替，活跃键数量保持在 500 左右
+    for round in 0..200u64 {
+        if round > 0 {
+            for i in 0..500 {
+                assert_eq!(map.remove(&((round - 1) * 500 + i)), Some(i));
+            }
+        }
+        for i in 0..500 {
+            map.insert(round * 500 + i, i).unwrap();
+        }
+        // 删除不留墓碑，不会触发重建或扩容
+        assert_eq!(map.deleted_count(), 0);
+        assert_eq!(map.len(), 500);
+        assert_eq!(map.capacity(), capacity);
+    }
+    for i in 0..500 {
+        assert_eq!(map.get(&(199 * 500 + i)), Some(&i));
+    }
+
+    // 全部删除后溢出计数归零
+    for i in 0..500 {
+        map.remove(&(199 * 500 + i)).unwrap();
+    }
+    assert!(map.is_empty());
+    assert!((0..map.group_count()).all(|g| map.chunk_meta(g) == Default::default()));
+}
+
+#[test]
+fn test_remove_clears_overflow() {
+    let mut map = F14VectorMap::with_hasher(FixedHasher).unwrap();
+    for i in 0..40 {
+        map.insert(i, i).unwrap();
+    }
+    assert_eq!(map.chunk_meta(0).outbound_overflow(), 24);
+
+    // 删除组 2 中的键，途经分组的溢出计数随之减少
+    for i in 32..40 {
+        assert_eq!(map.remove(&i), Some(i));
+    }
+    assert_eq!(map.chunk_meta(0).outbound_overflow(), 16);
+    assert_eq!(map.chunk_meta(1).outbound_overflow(), 0);
+    assert_eq!(map.chunk_meta(2).hosted_overflow(), 0);
+    assert_eq!(map.deleted_count(), 0);
+
+    // 组 0 中的空位被复用，组 1 中的键仍可找到
+    assert_eq!(map.remove(&0), Some(0));
+    map.insert(100, 100).unwrap();
+    for i in 1..32 {
+        assert_eq!(map.get(&i), Some(&i));
+    }
+    assert_eq!(map.get(&100), Some(&100));
+    assert_eq!(map.chunk_meta(0).outbound_overflow(), 16);
+}
+
+#[test]
+fn test_dense_storage() {
+    let mut map = F14VectorMap::<u32, String, RandomState>::new().unwrap();
+    for i in 0..100 {
+        map.insert(i, i.to_string()).unwrap();
+    }
+    // 键值对按插入顺序连续存放
+    assert_eq!(map.as_slice().len(), 100);
+    assert!(map.as_slice().iter().enumerate().all(|(i, (k, _))| *k == i as u32));
+    assert_eq!(map.iter().len(), map.len());
+
+    // 删除用末尾元素填补空洞，被搬移的键仍可找到
+    assert_eq!(map.remove(&10).as_deref(), Some("10"));
+    assert_eq!(map.as_slice()[10].0, 99);
+    assert_eq!(map.remove(&99).as_deref(), Some("99"));
+    assert_eq!(map.as_slice()[10].0, 98);
+    assert_eq!(map.remove(&98).as_deref(), Some("98"));
+    assert_eq!(map.as_slice()[10].0, 97);
+    assert_eq!(map.as_slice().len(), 97);
+    for i in (0..98).filter(|&i| i != 10) {
+        assert_eq!(map.get(&i), Some(&i.to_string()));
+    }
+
+    for (_, v) in map.iter_mut() {
+        v.push('!');
+    }
+    assert_eq!(map.get(&0).map(String::as_str), Some("0!"));
+
+    let mut into_iter = map.into_iter();
+    assert_eq!(into_iter.len(), 97);
+    assert_eq!(into_iter.next(), Some((0, "0!".to_string())));
+    assert_eq!(into_iter.next_back().map(|(k, _)| k), Some(96));
+    assert_eq!(into_iter.len(), 95);
+}
+
+#[test]
+fn test_dense_storage_drops() {
+    use std::rc::Rc;
+
+    let marker = Rc::new(());
+    let mut map = F14VectorMap::<u32, Rc<()>, RandomState>::new().unwrap();
+    for i in 0..50 {
+        map.insert(i, marker.clone()).unwrap();
+    }
+    assert_eq!(Rc::strong_count(&marker), 51);
+    for i in 0..10 {
+        map.remove(&i);
+    }
+    assert_eq!(Rc::strong_count(&marker), 41);
+
+    // 部分消耗的迭代器析构剩余元素
+    let mut into_iter = map.into_iter();
+    into_iter.next();
+    assert_eq!(Rc::strong_count(&marker), 40);
+    drop(into_iter);
+    assert_eq!(Rc::strong_count(&marker), 1);
+}
+
+/// 对任意存储策略执行相同的增删查改序列
+fn exercise_policy<P: f14vectormap::policy::StoragePolicy>() {
+    let mut map = f14vectormap::F14Map::<u64, String, RandomState, P>::new().unwrap();
+    for i in 0..500u64 {
+        assert_eq!(map.insert(i, i.to_string()).unwrap(), None);
+    }
+    assert_eq!(map.len(), 500);
+    assert_eq!(map.iter().len(), 500);
+    for i in (0..500u64).step_by(3) {
+        assert_eq!(map.remove(&i), Some(i.to_string()));
+    }
+    for i in 0..500u64 {
+        assert_eq!(map.contains_key(&i), i % 3 != 0);
+    }
+    *map.entry(1).or_default() += "!";
+    assert_eq!(map.get(&1).map(String::as_str), Some("1!"));
+    for (_, v) in map.iter_mut() {
+        v.push('?');
+    }
+
+    let mut keys: Vec<u64> = map.iter().map(|(k, _)| *k).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, (0..500u64).filter(|i| i % 3 != 0).collect::<Vec<_>>());
+
+    let mut pairs: Vec<(u64, String)> = map.into_iter().collect();
+    pairs.sort_unstable();
+    assert_eq!(pairs.len(), 333);
+    assert_eq!(pairs[0], (1, "1!?".to_string()));
+}
+
+#[test]
+fn test_storage_policies() {
+    use f14vectormap::policy::{FastPolicy, NodePolicy, ValuePolicy, VectorPolicy};
+
+    exercise_policy::<ValuePolicy>();
+    exercise_policy::<NodePolicy>();
+    exercise_policy::<VectorPolicy>();
+    exercise_policy::<FastPolicy>();
+}
+
+#[test]
+fn test_node_map_stable_addresses() {
+    use f14vectormap::F14NodeMap;
+
+    // 节点策略扩容只搬移指针，值的地址保持不变
+    let mut map = F14NodeMap::<u32, [u64; 16], RandomState>::new().unwrap();
+    map.insert(7, [7; 16]).unwrap();
+    let before = map.get(&7).unwrap() as *const [u64; 16];
+    for i in 100..1000 {
+        map.insert(i, [0; 16]).unwrap();
+    }
+    assert!(map.capacity() >= 1024);
+    assert_eq!(map.get(&7).unwrap() as *const [u64; 16], before);
+}
+
+#[test]
+fn test_value_and_fast_maps() {
+    use f14vectormap::{F14FastMap, F14ValueMap};
+    use std::rc::Rc;
+
+    let marker = Rc::new(());
+    let mut map = F14ValueMap::<u32, Rc<()>, RandomState>::new().unwrap();
+    for i in 0..100 {
+        map.insert(i, marker.clone()).unwrap();
+    }
+    map.remove(&0);
+    assert_eq!(Rc::strong_count(&marker), 100);
+    // 部分消耗的迭代器析构剩余元素
+    let mut into_iter = map.into_iter();
+    into_iter.next();
+    into_iter.next_back();
+    drop(into_iter);
+    assert_eq!(Rc::strong_count(&marker), 1);
+
+    // 小键值对与大键值对都能正常使用
+    let mut small = F14FastMap::<u32, u32, RandomState>::with_capacity(10).unwrap();
+    let mut large = F14FastMap::<u32, [u8; 64], RandomState>::with_capacity(10).unwrap();
+    for i in 0..200 {
+        small.insert(i, i).unwrap();
+        large.insert(i, [i as u8; 64]).unwrap();
+    }
+    for i in 0..200 {
+        assert_eq!(small.remove(&i), Some(i));
+        assert_eq!(large.get(&i).map(|v| v[0]), Some(i as u8));
+    }
+    assert!(small.is_empty());
+    assert_eq!(large.len(), 200);
+}
+
+#[test]
+fn test_vector_set() {
+    use f14vectormap::F14VectorSet;
+
+    let mut set = F14VectorSet::<String, RandomState>::new().unwrap();
+    assert!(set.insert("a".to_string()).unwrap());
+    assert!(set.insert("b".to_string()).unwrap());
+    assert!(!set.insert("a".to_string()).unwrap());
+    assert_eq!(set.len(), 2);
+    assert!(set.contains("a"));
+    assert_eq!(set.get("b").map(String::as_str), Some("b"));
+
+    // replace 换入新元素并返回旧元素
+    let replacement = "a".to_string();
+    let replacement_ptr = replacement.as_ptr();
+    assert_eq!(set.replace(replacement).unwrap().as_deref(), Some("a"));
+    assert_eq!(set.get("a").unwrap().as_ptr(), replacement_ptr);
+    assert_eq!(set.replace("c".to_string()).unwrap(), None);
+
+    assert_eq!(set.take("c").as_deref(), Some("c"));
+    assert!(set.remove("b"));
+    assert!(!set.remove("b"));
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec!["a"]);
+    assert_eq!(format!("{:?}", set), "{\"a\"}");
+    assert_eq!(set.into_iter().collect::<Vec<_>>(), vec!["a".to_string()]);
+}
+
+#[test]
+fn test_set_algebra() {
+    use f14vectormap::F14VectorSet;
+
+    let mut a = F14VectorSet::<u32, RandomState>::new().unwrap();
+    let mut b = F14VectorSet::<u32, RandomState>::new().unwrap();
+    for i in 0..10 {
+        a.insert(i).unwrap();
+    }
+    for i in 5..20 {
+        b.insert(i).unwrap();
+    }
+
+    let sorted = |iter: &mut dyn Iterator<Item = &u32>| {
+        let mut values: Vec<u32> = iter.copied().collect();
+        values.sort_unstable();
+        values
+    };
+    assert_eq!(sorted(&mut a.union(&b)), (0..20).collect::<Vec<_>>());
+    assert_eq!(sorted(&mut a.intersection(&b)), (5..10).collect::<Vec<_>>());
+    assert_eq!(sorted(&mut b.intersection(&a)), (5..10).collect::<Vec<_>>());
+    assert_eq!(sorted(&mut a.difference(&b)), (0..5).collect::<Vec<_>>());
+    assert_eq!(sorted(&mut b.difference(&a)), (10..20).collect::<Vec<_>>());
+    assert_eq!(
+        sorted(&mut a.symmetric_difference(&b)),
+        (0..5).chain(10..20).collect::<Vec<_>>()
+    );
+
+    assert!(!a.is_disjoint(&b));
+    assert!(!a.is_subset(&b));
+    let mut c = F14VectorSet::<u32, RandomState>::new().unwrap();
+    for i in 6..9 {
+        c.insert(i).unwrap();
+    }
+    assert!(c.is_subset(&a) && c.is_subset(&b));
+    assert!(a.is_superset(&c));
+    let mut d = F14VectorSet::<u32, RandomState>::new().unwrap();
+    d.insert(100).unwrap();
+    assert!(d.is_disjoint(&a));
+    assert!(F14VectorSet::<u32, RandomState>::default().is_subset(&d));
+}
+
+#[test]
+fn test_reserve_and_shrink() {
+    let mut map = F14VectorMap::<u64, u64, RandomState>::new().unwrap();
+    map.reserve(1000);
+    let capacity = map.capacity();
+    assert!(capacity * 7 / 10 >= 1000);
+
+    // 预留后插入不再扩容
+    for i in 0..1000 {
+        map.insert(i, i).unwrap();
+    }
+    assert_eq!(map.capacity(), capacity);
+    map.reserve(0);
+    assert_eq!(map.capacity(), capacity);
+
+    // 缩容保留所有元素
+    for i in 10..1000 {
+        map.remove(&i);
+    }
+    map.shrink_to(100);
+    assert_eq!(map.capacity(), 256);
+    map.shrink_to_fit();
+    assert_eq!(map.capacity(), 16);
+    for i in 0..10 {
+        assert_eq!(map.get(&i), Some(&i));
+    }
+    assert_eq!(map.as_slice().len(), 10);
+
+    // 不会扩大容量
+    map.shrink_to(10_000);
+    assert_eq!(map.capacity(), 16);
+
+    map.clear();
+    map.shrink_to_fit();
+    assert_eq!(map.capacity(), 0);
+    map.insert(1, 1).unwrap();
+    assert_eq!(map.get(&1), Some(&1));
+}
+
+#[test]
+fn test_try_reserve() {
+    use f14vectormap::F14NodeMap;
+
+    let mut map = F14NodeMap::<u64, u64, RandomState>::new().unwrap();
+    for i in 0..5 {
+        map.insert(i, i).unwrap();
+    }
+    let capacity = map.capacity();
+    assert_eq!(map.try_reserve(usize::MAX), Err(MapError::CapacityExceeded));
+    assert_eq!(map.capacity(), capacity);
+    assert_eq!(map.len(), 5);
+
+    assert_eq!(map.try_reserve(200), Ok(()));
+    let capacity = map.capacity();
+    for i in 5..205 {
+        map.insert(i, i).unwrap();
+    }
+    assert_eq!(map.capacity(), capacity);
+    map.shrink_to_fit();
+    assert_eq!(map.capacity(), 512);
+    assert!((0..205).all(|i| map.get(&i) == Some(&i)));
+}
+
+/// 统计分配次数与未释放字节数的分配器
+#[derive(Default)]
+struct CountingAlloc {
+    allocations: std::cell::Cell<usize>,
+    live_bytes: std::cell::Cell<usize>,
+}
+
+unsafe impl f14vectormap::allocator::Allocator for CountingAlloc {
+    fn allocate(
+        &self,
+        layout: std::alloc::Layout,
+    ) -> Result<std::ptr::NonNull<u8>, f14vectormap::allocator::AllocError> {
+        self.allocations.set(self.allocations.get() + 1);
+        self.live_bytes.set(self.live_bytes.get() + layout.size());
+        std::ptr::NonNull::new(unsafe { std::alloc::alloc(layout) })
+            .ok_or(f14vectormap::allocator::AllocError)
+    }
+
+    unsafe fn deallocate(&self, ptr: std::ptr::NonNull<u8>, layout: std::alloc::Layout) {
+        self.live_bytes.set(self.live_bytes.get() - layout.size());
+        unsafe { std::alloc::dealloc(ptr.as_ptr(), layout) };
+    }
+}
+
+fn exercise_allocator<P: f14vectormap::policy::StoragePolicy>() {
+    let alloc = CountingAlloc::default();
+    {
+        let mut map =
+            f14vectormap::F14Map::<u64, String, RandomState, P, &CountingAlloc>::new_in(&alloc).unwrap();
+        assert_eq!(alloc.allocations.get(), 0);
+        for i in 0..300u64 {
+            map.insert(i, i.to_string()).unwrap();
+        }
+        for i in (0..300u64).step_by(2) {
+            assert_eq!(map.remove(&i), Some(i.to_string()));
+        }
+        map.shrink_to_fit();
+        assert!(alloc.allocations.get() > 0);
+        assert!(alloc.live_bytes.get() > 0);
+        assert!(std::ptr::eq(*map.allocator(), &alloc));
+
+        // 消耗迭代器中途丢弃，剩余元素与表内存同样归还分配器
+        let mut iter = map.into_iter();
+        assert!(iter.next().is_some());
+    }
+    assert_eq!(alloc.live_bytes.get(), 0);
+}
+
+#[test]
+fn test_custom_allocator() {
+    use f14vectormap::policy::{FastPolicy, NodePolicy, ValuePolicy, VectorPolicy};
+
+    exercise_allocator::<ValuePolicy>();
+    exercise_allocator::<NodePolicy>();
+    exercise_allocator::<VectorPolicy>();
+    exercise_allocator::<FastPolicy>();
+
+    // 节点策略的每个键值对都经过分配器
+    let alloc = CountingAlloc::default();
+    let mut map =
+        f14vectormap::F14NodeMap::<u64, u64, RandomState, &CountingAlloc>::with_capacity_in(64, &alloc)
+            .unwrap();
+    let table_allocations = alloc.allocations.get();
+    for i in 0..10 {
+        map.insert(i, i).unwrap();
+    }
+    assert_eq!(alloc.allocations.get(), table_allocations + 10);
+    map.clear();
+    drop(map);
+    assert_eq!(alloc.live_bytes.get(), 0);
+
+    let alloc = CountingAlloc::default();
+    let mut set = f14vectormap::F14VectorSet::<u64, RandomState, &CountingAlloc>::with_capacity_and_hasher_in(
+        16,
+        RandomState::new(),
+        &alloc,
+    )
+    .unwrap();
+    assert!(set.insert(7).unwrap());
+    assert!(alloc.live_bytes.get() > 0);
+    drop(set);
+    assert_eq!(alloc.live_bytes.get(), 0);
+}
+
+#[test]
+fn test_vector_dense_array_follows_load_factor() {
+    type Pair = (u64, [u64; 16]);
+
+    let alloc = CountingAlloc::default();
+    let mut map =
+        f14vectormap::F14VectorMap::<u64, [u64; 16], RandomState, &CountingAlloc>::with_capacity_in(700, &alloc)
+            .unwrap();
+    let capacity = map.capacity();
+    // 稠密数组按负载上限而不是分组槽位数分配
+    assert!(alloc.live_bytes.get() < capacity * std::mem::size_of::<Pair>());
+
+    // 填到负载上限不需要再分配，再插入一个时稠密数组随分组表一起增长
+    let allocations = alloc.allocations.get();
+    let max_len = capacity * 7 / 10;
+    for i in 0..max_len as u64 {
+        map.insert(i, [i; 16]).unwrap();
+    }
+    assert_eq!(alloc.allocations.get(), allocations);
+    assert_eq!(map.capacity(), capacity);
+    map.insert(max_len as u64, [0; 16]).unwrap();
+    assert!(map.capacity() > capacity);
+    assert!((0..max_len as u64).all(|i| map.get(&i) == Some(&[i; 16])));
+
+    map.shrink_to_fit();
+    assert!((0..max_len as u64).all(|i| map.get(&i) == Some(&[i; 16])));
+    drop(map);
+    assert_eq!(alloc.live_bytes.get(), 0);
+}
+
+#[test]
+fn test_failing_allocator() {
+    use f14vectormap::allocator::{AllocError, Allocator};
+
+    #[derive(Clone, Default)]
+    struct NoMemory;
+
+    unsafe impl Allocator for NoMemory {
+        fn allocate(&self, _: std::alloc::Layout) -> Result<std::ptr::NonNull<u8>, AllocError> {
+            Err(AllocError)
+        }
+
+        unsafe fn deallocate(&self, _: std::ptr::NonNull<u8>, _: std::alloc::Layout) {
+            unreachable!("nothing was allocated");
+        }
+    }
+
+    let mut map = f14vectormap::F14ValueMap::<u64, u64, RandomState, NoMemory>::new_in(NoMemory).unwrap();
+    assert!(matches!(map.insert(1, 1), Err(MapError::AllocationFailed { .. })));
+    assert!(map.is_empty());
+    assert!(matches!(
+        f14vectormap::F14VectorMap::<u64, u64, RandomState, NoMemory>::with_capacity_in(10, NoMemory),
+        Err(MapError::AllocationFailed { .. })
+    ));
+    let default_map = f14vectormap::F14FastMap::<u64, u64, RandomState, NoMemory>::default();
+    assert_eq!(default_map.capacity(), 0);
+}
+
+#[test]
+fn test_apply_batch() {
+    use f14vectormap::BatchReport;
+
+    let mut map = F14VectorMap::<u64, String, RandomState>::new().unwrap();
+    assert_eq!(map.batch_version(), 0);
+
+    let report = map.apply_batch((0..100u64).map(|i| (i, Some(i.to_string())))).unwrap();
+    assert_eq!(report, BatchReport { inserted: 100, updated: 0, removed: 0, missing: 0, batch_version: 1 });
+    assert_eq!(map.len(), 100);
+
+    // 同一批中的写入与删除按顺序生效
+    let batch = vec![
+        (1, Some("one".to_string())),
+        (2, None),
+        (2, Some("two".to_string())),
+        (3, None),
+        (1000, None),
+        (1001, Some("new".to_string())),
+    ];
+    let report = map.apply_batch(batch).unwrap();
+    assert_eq!(report, BatchReport { inserted: 2, updated: 1, removed: 2, missing: 1, batch_version: 2 });
+    assert_eq!(map.batch_version(), 2);
+    assert_eq!(map.get(&1).map(String::as_str), Some("one"));
+    assert_eq!(map.get(&2).map(String::as_str), Some("two"));
+    assert_eq!(map.get(&3), None);
+    assert_eq!(map.get(&1001).map(String::as_str), Some("new"));
+    assert_eq!(map.len(), 100);
+
+    // 批量更新之外的修改不改变批量版本号
+    map.insert(5000, String::new()).unwrap();
+    map.remove(&5000);
+    map.entry(5001).or_default();
+    map.retain(|&key, _| key != 5001);
+    map.get_mut(&1).unwrap().push('!');
+    assert_eq!(map.batch_version(), 2);
+    map.clear();
+    assert_eq!(map.batch_version(), 2);
+    assert_eq!(map.apply_batch(std::iter::empty()).unwrap().batch_version, 3);
+}
+
+fn exercise_compact<P: f14vectormap::policy::StoragePolicy>() {
+    use f14vectormap::f14_map::SlotState;
+
+    let mut map = f14vectormap::F14Map::<u64, String, RandomState, P>::with_capacity(256).unwrap();
+    let capacity = map.capacity();
+    for i in 0..179u64 {
+        map.insert(i, i.to_string()).unwrap();
+    }
+    let outbound = |map: &f14vectormap::F14Map<u64, String, RandomState, P>| -> usize {
+        (0..map.group_count()).map(|g| map.chunk_meta(g).outbound_overflow() as usize).sum()
+    };
+    let before = outbound(&map);
+
+    // 删除一半，再留下一些墓碑
+    for i in (0..179u64).step_by(2) {
+        assert_eq!(map.remove(&i), Some(i.to_string()));
+    }
+    let tombstones: Vec<usize> = (0..capacity)
+        .filter(|&slot| map.slot_state(slot) == SlotState::Full)
+        .take(10)
+        .collect();
+    let mut buried = Vec::new();
+    for slot in tombstones {
+        buried.push(unsafe { map.replace_slot_state(slot, SlotState::Deleted) }.0);
+    }
+    assert_eq!(map.deleted_count(), 10);
+
+    map.compact();
+    assert_eq!(map.deleted_count(), 0);
+    assert_eq!(map.capacity(), capacity);
+    assert_eq!(map.len(), 89 - 10);
+    assert!(outbound(&map) <= before);
+    for i in 0..179u64 {
+        let expected = (i % 2 == 1 && !buried.contains(&i)).then(|| i.to_string());
+        assert_eq!(map.get(&i), expected.as_ref(), "key {i}");
+    }
+
+    // 整理后的溢出计数与元素完全对应，全部删除后归零
+    let keys: Vec<u64> = map.iter().map(|(key, _)| *key).collect();
+    for key in keys {
+        assert!(map.remove(&key).is_some());
+    }
+    assert!((0..map.group_count()).all(|g| map.chunk_meta(g) == Default::default()));
+}
+
+#[test]
+fn test_compact_in_place() {
+    use f14vectormap::policy::{FastPolicy, NodePolicy, ValuePolicy, VectorPolicy};
+
+    exercise_compact::<ValuePolicy>();
+    exercise_compact::<NodePolicy>();
+    exercise_compact::<VectorPolicy>();
+    exercise_compact::<FastPolicy>();
+
+    // 所有键落在同一起始组时，整理要把溢出元素依次排回探测序列
+    let mut map = F14VectorMap::<u64, u64, FixedHasher>::with_capacity_and_hasher(64, FixedHasher).unwrap();
+    for i in 0..40 {
+        map.insert(i, i).unwrap();
+    }
+    for i in 0..20 {
+        map.remove(&i).unwrap();
+    }
+    map.compact();
+    for i in 0..40 {
+        assert_eq!(map.get(&i), (i >= 20).then_some(&i));
+    }
+    for i in 40..44 {
+        map.insert(i, i).unwrap();
+    }
+    assert_eq!(map.len(), 24);
+}
+
+#[test]
+fn test_raw_hash_api() {
+    use f14vectormap::entry::{Entry, RawEntryMut};
+
+    let hasher = RandomState::new();
+    let mut names = F14VectorMap::<String, u32, RandomState>::with_hasher(hasher.clone()).unwrap();
+    let mut scores = f14vectormap::F14ValueMap::<String, f32, RandomState>::with_hasher(hasher).unwrap();
+
+    // 同一哈希构建器的克隆得到相同的哈希，算一次即可在两个映射中使用
+    for i in 0..100u32 {
+        let key = format!("feature-{i}");
+        let hash = names.hash_of(&key);
+        assert_eq!(hash, scores.hash_of(key.as_str()));
+        assert_eq!(names.insert_with_hash(hash, key.clone(), i).unwrap(), None);
+        assert_eq!(scores.insert_with_hash(hash, key, i as f32).unwrap(), None);
+    }
+    for i in 0..100u32 {
+        let key = format!("feature-{i}");
+        let hash = names.hash_of(key.as_str());
+        assert_eq!(names.get_with_hash(hash, key.as_str()), Some(&i));
+        assert_eq!(scores.get_with_hash(hash, &key), Some(&(i as f32)));
+        *names.get_mut_with_hash(hash, key.as_str()).unwrap() += 1000;
+    }
+    let hash = names.hash_of("feature-7");
+    assert_eq!(names.remove_with_hash(hash, "feature-7"), Some(1007));
+    assert_eq!(names.get_with_hash(hash, "feature-7"), None);
+    match names.entry_with_hash(hash, "feature-7".to_string()) {
+        Entry::Vacant(entry) => {
+            entry.insert(7);
+        }
+        Entry::Occupied(_) => panic!("feature-7 was removed"),
+    }
+    assert_eq!(names.get("feature-7"), Some(&7));
+
+    // 原始条目：只用哈希和相等判断，不构造 `String`
+    let hash = names.hash_of("feature-42");
+    assert_eq!(
+        names.raw_entry().from_hash(hash, |key| key == "feature-42"),
+        Some((&"feature-42".to_string(), &1042))
+    );
+    assert_eq!(names.raw_entry().from_key_hashed_nocheck(hash, "feature-43"), None);
+
+    match names.raw_entry_mut().from_hash(hash, |key| key == "feature-42") {
+        RawEntryMut::Occupied(entry) => assert_eq!(entry.remove_entry(), ("feature-42".to_string(), 1042)),
+        RawEntryMut::Vacant(_) => panic!("feature-42 should exist"),
+    }
+    let hash = names.hash_of("new");
+    let (key, value) = match names.raw_entry_mut().from_key_hashed_nocheck(hash, "new") {
+        RawEntryMut::Vacant(entry) => entry.insert("new".to_string(), 1),
+        RawEntryMut::Occupied(_) => panic!("new should not exist"),
+    };
+    assert_eq!((key.as_str(), *value), ("new", 1));
+    let (_, value) = names
+        .raw_entry_mut()
+        .from_hash(hash, |key| key == "new")
+        .or_insert_with(|| unreachable!());
+    *value += 1;
+    assert_eq!(names.get("new"), Some(&2));
+    assert_eq!(names.len(), 100);
+}
+
+#[test]
+fn test_get_batch() {
+    use f14vectormap::f14_map::BATCH_PREFETCH;
+
+    let mut vector = F14VectorMap::<u64, u64>::new().unwrap();
+    let mut value = f14vectormap::F14ValueMap::<u64, u64>::new().unwrap();
+    // 空表也能批量查找
+    assert_eq!(vector.get_batch(&[1u64, 2, 3]), [None, None, None]);
+    assert!(value.get_batch::<u64>(&[]).is_empty());
+
+    for i in 0..1000u64 {
+        vector.insert(i, i * 3).unwrap();
+        value.insert(i, i * 3).unwrap();
+    }
+    // 命中与未命中交错，长度不是批大小的整数倍
+    let keys: Vec<u64> = (0..(BATCH_PREFETCH as u64 * 7 + 5)).map(|i| i * 13).collect();
+    let expected: Vec<Option<&u64>> = keys.iter().map(|key| vector.get(key)).collect();
+    assert!(expected.iter().any(Option::is_none));
+    assert_eq!(vector.get_batch(&keys), expected);
+    assert_eq!(value.get_batch(&keys), expected);
+
+    let mut out = vec![None; keys.len()];
+    vector.get_batch_into(&keys, &mut out);
+    assert_eq!(out, expected);
+
+    // 借用形式的键
+    let mut names = F14VectorMap::<String, usize>::new().unwrap();
+    for i in 0..50 {
+        names.insert(format!("k{i}"), i).unwrap();
+    }
+    let lookups = ["k3", "k49", "k50", "k0"];
+    assert_eq!(names.get_batch(&lookups.map(String::from)), [Some(&3), Some(&49), None, Some(&0)]);
+}
+
+fn exercise_bulk_removal<P: f14vectormap::policy::StoragePolicy>() {
+    type Map<P> = f14vectormap::F14Map<u64, String, RandomState, P>;
+
+    let mut map = Map::<P>::new().unwrap();
+    for i in 0..500u64 {
+        map.insert(i, i.to_string()).unwrap();
+    }
+
+    // retain 可以顺带修改保留下来的值
+    map.retain(|key, value| {
+        value.push('!');
+        key % 3 != 0
+    });
+    assert_eq!(map.len(), 333);
+    assert_eq!(map.deleted_count(), 0);
+    for i in 0..500u64 {
+        let expected = (i % 3 != 0).then(|| format!("{i}!"));
+        assert_eq!(map.get(&i), expected.as_ref(), "key {i}");
+    }
+
+    // extract_if 惰性移出，提前析构时其余键值对保留
+    let mut extracted: Vec<u64> = map.extract_if(|key, _| key % 2 == 0).take(10).map(|(key, _)| key).collect();
+    assert_eq!(extracted.len(), 10);
+    assert_eq!(map.len(), 323);
+    extracted.extend(map.extract_if(|key, _| key % 2 == 0).map(|(key, value)| {
+        assert_eq!(value, format!("{key}!"));
+        key
+    }));
+    extracted.sort_unstable();
+    let expected: Vec<u64> = (0..500).filter(|i| i % 3 != 0 && i % 2 == 0).collect();
+    assert_eq!(extracted, expected);
+    assert!(map.iter().all(|(key, _)| key % 2 == 1 && key % 3 != 0));
+    assert_eq!(map.len(), map.iter().count());
+
+    // drain 保留容量，析构时释放未取出的键值对
+    let capacity = map.capacity();
+    let len = map.len();
+    let mut drain = map.drain();
+    assert_eq!(drain.len(), len);
+    let (key, value) = drain.next().unwrap();
+    assert_eq!(value, format!("{key}!"));
+    assert_eq!(drain.len(), len - 1);
+    drop(drain);
+    assert!(map.is_empty());
+    assert_eq!(map.capacity(), capacity);
+    assert_eq!(map.get(&key), None);
+    assert!((0..map.group_count()).all(|g| map.chunk_meta(g) == Default::default()));
+
+    // 排空后可以继续使用，被遗忘的迭代器也不会留下悬空的键值对
+    for i in 0..100u64 {
+        map.insert(i, i.to_string()).unwrap();
+    }
+    assert_eq!(map.capacity(), capacity);
+    let drained: usize = map.drain().count();
+    assert_eq!(drained, 100);
+    map.insert(7, "seven".to_string()).unwrap();
+    std::mem::forget(map.drain());
+    assert!(map.is_empty());
+    assert_eq!(map.get(&7), None);
+    map.insert(8, "eight".to_string()).unwrap();
+    assert_eq!(map.get(&8).map(String::as_str), Some("eight"));
+}
+
+#[test]
+fn test_retain_drain_extract_if() {
+    use f14vectormap::policy::{FastPolicy, NodePolicy, ValuePolicy, VectorPolicy};
+
+    exercise_bulk_removal::<ValuePolicy>();
+    exercise_bulk_removal::<NodePolicy>();
+    exercise_bulk_removal::<VectorPolicy>();
+    exercise_bulk_removal::<FastPolicy>();
+
+    // 同一探测链上的键被删除后，溢出计数随之撤销，链上其余键仍可查到
+    let mut map = F14VectorMap::<u64, u64, FixedHasher>::with_capacity_and_hasher(64, FixedHasher).unwrap();
+    for i in 0..40 {
+        map.insert(i, i).unwrap();
+    }
+    map.retain(|key, _| key % 4 == 0);
+    assert_eq!(map.len(), 10);
+    for i in 0..40 {
+        assert_eq!(map.get(&i), (i % 4 == 0).then_some(&i));
+    }
+    map.retain(|_, _| false);
+    assert!(map.is_empty());
+    assert!((0..map.group_count()).all(|g| map.chunk_meta(g) == Default::default()));
+}
+
+fn exercise_collection_traits<P: f14vectormap::policy::StoragePolicy>() {
+    use f14vectormap::f14_map::SlotState;
+    type Map<P> = f14vectormap::F14Map<u64, String, RandomState, P>;
+
+    let map: Map<P> = (0..200u64).map(|i| (i, i.to_string())).collect();
+    assert_eq!(map.len(), 200);
+    assert_eq!(map[&17], "17");
+
+    // 克隆保留容量、墓碑与溢出元数据，并且与原映射互不影响
+    let mut source = map.clone();
+    let tombstone = (0..source.capacity()).find(|&slot| source.slot_state(slot) == SlotState::Full).unwrap();
+    let (buried, _) = unsafe { source.replace_slot_state(tombstone, SlotState::Deleted) };
+    let mut copy = source.clone();
+    assert_eq!(copy.capacity(), source.capacity());
+    assert_eq!(copy.deleted_count(), 1);
+    assert!((0..copy.group_count()).all(|g| copy.chunk_meta(g) == source.chunk_meta(g)));
+    assert_eq!(copy, source);
+    assert_ne!(copy, map);
+    assert_eq!(copy.get(&buried), None);
+    copy.insert(buried, "back".to_string()).unwrap();
+    let probe = if buried == 1 { 2 } else { 1 };
+    *copy.get_mut(&probe).unwrap() = "changed".to_string();
+    assert_eq!(source[&probe], probe.to_string());
+    assert_eq!(source.len(), 199);
+    assert_eq!(copy.len(), 200);
+
+    // 相等与存储顺序无关
+    let reversed: Map<P> = (0..200u64).rev().map(|i| (i, i.to_string())).collect();
+    assert_eq!(reversed, map);
+
+    // 扩展：后出现的值覆盖先前的值
+    let mut extended = Map::<P>::from([(1, "one".to_string()), (2, "two".to_string())]);
+    extended.extend([(2, "deux".to_string()), (3, "trois".to_string())]);
+    assert_eq!(extended.len(), 3);
+    assert_eq!(extended[&2], "deux");
+    extended.try_extend((10..20).map(|i| (i, i.to_string()))).unwrap();
+    assert_eq!(extended.len(), 13);
+
+    let empty = Map::<P>::new().unwrap();
+    assert_eq!(empty.clone(), empty);
+    assert_eq!(empty.clone().capacity(), 0);
+}
+
+#[test]
+fn test_collection_traits() {
+    use f14vectormap::policy::{FastPolicy, NodePolicy, ValuePolicy, VectorPolicy};
+
+    exercise_collection_traits::<ValuePolicy>();
+    exercise_collection_traits::<NodePolicy>();
+    exercise_collection_traits::<VectorPolicy>();
+    exercise_collection_traits::<FastPolicy>();
+
+    // 按引用扩展（键值均为 Copy）
+    let source = F14VectorMap::<u32, u32>::from([(1, 10), (2, 20)]);
+    let mut target = F14VectorMap::<u32, u32>::new().unwrap();
+    target.extend(&source);
+    target.extend([(&3, &30)]);
+    assert_eq!(target.len(), 3);
+    assert_eq!(target[&3], 30);
+    assert_eq!(target.iter().map(|(_, value)| value).sum::<u32>(), 60);
+
+    // 借用形式的索引
+    let names: F14VectorMap<String, usize> = ["a", "bb", "ccc"].iter().map(|s| (s.to_string(), s.len())).collect();
+    assert_eq!(names["bb"], 2);
+}
+
+#[test]
+#[should_panic(expected = "key not found")]
+fn test_index_missing_key_panics() {
+    let map = F14VectorMap::<u32, u32>::from([(1, 1)]);
+    let _ = map[&2];
+}
+
+/// 每次前进后 `len()` 与 `size_hint()` 都等于剩余数量，耗尽后保持 `None`
+fn assert_exact_size<I: ExactSizeIterator>(mut iter: I, expected: usize) -> Vec<I::Item> {
+    let mut items = Vec::new();
+    for remaining in (1..=expected).rev() {
+        assert_eq!(iter.len(), remaining);
+        assert_eq!(iter.size_hint(), (remaining, Some(remaining)));
+        items.push(iter.next().expect("iterator ended early"));
+    }
+    assert_eq!(iter.len(), 0);
+    assert!(iter.next().is_none());
+    assert!(iter.next().is_none());
+    items
+}
+
+fn exercise_views<P: f14vectormap::policy::StoragePolicy>() {
+    type Map<P> = f14vectormap::F14Map<u64, String, RandomState, P>;
+
+    let map: Map<P> = (0..100u64).map(|i| (i, i.to_string())).collect();
+
+    // 各视图与 iter() 的顺序一致
+    let pairs: Vec<(u64, String)> = map.iter().map(|(key, value)| (*key, value.clone())).collect();
+    assert_eq!(assert_exact_size(map.iter(), 100).len(), 100);
+    let keys: Vec<u64> = assert_exact_size(map.keys(), 100).into_iter().copied().collect();
+    assert_eq!(keys, pairs.iter().map(|(key, _)| *key).collect::<Vec<_>>());
+    let values: Vec<&String> = assert_exact_size(map.values(), 100);
+    assert!(values.iter().zip(&pairs).all(|(value, (_, expected))| *value == expected));
+    assert_eq!(map.keys().next_back(), pairs.last().map(|(key, _)| key));
+    assert_eq!(map.values().next_back(), pairs.last().map(|(_, value)| value));
+
+    let mut map = map;
+    for value in map.values_mut() {
+        value.push('!');
+    }
+    assert_eq!(map.values_mut().len(), 100);
+    assert!(map.iter().all(|(key, value)| *value == format!("{key}!")));
+
+    let mut keys: Vec<u64> = assert_exact_size(map.clone().into_keys(), 100);
+    keys.sort_unstable();
+    assert_eq!(keys, (0..100).collect::<Vec<_>>());
+    let values = assert_exact_size(map.clone().into_values(), 100);
+    assert!(values.iter().all(|value| value.ends_with('!')));
+    let mut into_keys = map.clone().into_keys();
+    assert!(into_keys.next_back().is_some());
+    assert_eq!(into_keys.len(), 99);
+
+    let capacity = map.capacity();
+    assert_eq!(assert_exact_size(map.drain(), 100).len(), 100);
+    assert!(map.is_empty());
+    assert_eq!(map.capacity(), capacity);
+}
+
+#[test]
+fn test_views_and_exact_size() {
+    use f14vectormap::policy::{FastPolicy, NodePolicy, ValuePolicy, VectorPolicy};
+
+    exercise_views::<ValuePolicy>();
+    exercise_views::<NodePolicy>();
+    exercise_views::<VectorPolicy>();
+    exercise_views::<FastPolicy>();
+}
+
+fn exercise_sparse_iteration<P: f14vectormap::policy::StoragePolicy>() {
+    use std::collections::BTreeSet;
+
+    for len in [0u64, 1, 3, 17, 100, 700] {
+        let mut map = f14vectormap::F14Map::<u64, u64, RandomState, P>::with_capacity(1024).unwrap();
+        for i in 0..len {
+            map.insert(i, i).unwrap();
+        }
+        let expected: BTreeSet<u64> = (0..len).collect();
+        assert_eq!(map.iter().map(|(key, _)| *key).collect::<BTreeSet<_>>(), expected);
+        assert_eq!(map.iter().rev().map(|(key, _)| *key).collect::<BTreeSet<_>>(), expected);
+        let forward: Vec<u64> = map.keys().copied().collect();
+        let mut backward: Vec<u64> = map.keys().rev().copied().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+
+        // 两端交替前进，在同一分组内相遇时既不重复也不遗漏
+        let mut iter = map.iter();
+        let mut seen = BTreeSet::new();
+        let mut from_front = true;
+        while let Some((key, _)) = if from_front { iter.next() } else { iter.next_back() } {
+            assert!(seen.insert(*key), "key {key} yielded twice");
+            from_front = !from_front;
+        }
+        assert_eq!(seen, expected);
+
+        // 先从两端各取一部分，再用 fold 消耗剩余元素
+        let mut iter = map.iter();
+        let mut seen: BTreeSet<u64> = iter.by_ref().take(len as usize / 3).map(|(key, _)| *key).collect();
+        seen.extend(iter.by_ref().rev().take(len as usize / 3).map(|(key, _)| *key));
+        let rest = iter.fold(0, |count, (key, _)| {
+            assert!(seen.insert(*key), "key {key} yielded twice");
+            count + 1
+        });
+        assert_eq!(rest, len as usize - 2 * (len as usize / 3));
+        assert_eq!(seen, expected);
+    }
+}
+
+#[test]
+fn test_sparse_iteration() {
+    use f14vectormap::policy::{FastPolicy, NodePolicy, ValuePolicy, VectorPolicy};
+
+    exercise_sparse_iteration::<ValuePolicy>();
+    exercise_sparse_iteration::<NodePolicy>();
+    exercise_sparse_iteration::<VectorPolicy>();
+    exercise_sparse_iteration::<FastPolicy>();
+}
@@ -0,0 +1,142 @@
+//! 并行迭代与批量操作测试（`cargo test --features rayon`）
+
+#![cfg(feature = "rayon")]
+
+use f14vectormap::{
+    F14Map,
+    policy::{FastPolicy, NodePolicy, StoragePolicy, ValuePolicy, VectorPolicy},
+};
+use rayon::prelude::*;
+use std::{collections::hash_map::RandomState, sync::Arc};
+
+type Map<V, P> = F14Map<u64, V, RandomState, P>;
+
+fn filled<P: StoragePolicy>(len: u64) -> Map<u64, P> {
+    let mut map = Map::<u64, P>::new().unwrap();
+    for i in 0..len {
+        map.insert(i, i * 10).unwrap();
+    }
+    map
+}
+
+fn exercise_par_iter<P: StoragePolicy>() {
+    for len in [0u64, 1, 15, 17, 1000] {
+        let mut map = filled::<P>(len);
+        assert_eq!(map.par_iter().count(), len as usize);
+        assert_eq!(map.par_iter().map(|(_, value)| *value).sum::<u64>(), map.values().sum::<u64>());
+
+        let mut keys: Vec<u64> = map.par_iter().map(|(key, _)| *key).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, (0..len).collect::<Vec<_>>());
+
+        map.par_iter_mut().for_each(|(key, value)| *value += key);
+        for i in 0..len {
+            assert_eq!(map.get(&i), Some(&(i * 11)));
+        }
+        (&mut map).into_par_iter().for_each(|(_, value)| *value = 0);
+        assert!((&map).into_par_iter().all(|(_, value)| *value == 0));
+    }
+}
+
+#[test]
+fn test_par_iter() {
+    exercise_par_iter::<ValuePolicy>();
+    exercise_par_iter::<NodePolicy>();
+    exercise_par_iter::<VectorPolicy>();
+    exercise_par_iter::<FastPolicy>();
+}
+
+fn exercise_par_extend<P: StoragePolicy>() {
+    let mut map = Map::<u64, P>::new().unwrap();
+    map.par_extend((0..1000u64).into_par_iter().map(|i| (i, i)));
+    assert_eq!(map.len(), 1000);
+
+    // 与已有键重叠的部分覆盖旧值
+    map.try_par_extend((500..1500u64).into_par_iter().map(|i| (i, i + 1))).unwrap();
+    assert_eq!(map.len(), 1500);
+    for i in 0..1500 {
+        let expected = if i < 500 { i } else { i + 1 };
+        assert_eq!(map.get(&i), Some(&expected), "key {i}");
+    }
+
+    map.par_extend(Vec::<(u64, u64)>::new());
+    assert_eq!(map.len(), 1500);
+}
+
+#[test]
+fn test_par_extend() {
+    exercise_par_extend::<ValuePolicy>();
+    exercise_par_extend::<NodePolicy>();
+    exercise_par_extend::<VectorPolicy>();
+    exercise_par_extend::<FastPolicy>();
+}
+
+fn exercise_par_retain<P: StoragePolicy>() {
+    let mut map = filled::<P>(1000);
+    map.par_retain(|key, value| {
+        *value += 1;
+        key % 3 == 0
+    });
+    assert_eq!(map.len(), 334);
+    for i in 0..1000 {
+        assert_eq!(map.get(&i), (i % 3 == 0).then_some(&(i * 10 + 1)), "key {i}");
+    }
+    assert_eq!(map.deleted_count(), 0);
+
+    // 删除后的表可以继续插入
+    for i in 0..1000 {
+        map.insert(i, i).unwrap();
+    }
+    assert_eq!(map.len(), 1000);
+
+    map.par_retain(|_, _| false);
+    assert!(map.is_empty());
+    assert_eq!(map.iter().count(), 0);
+}
+
+#[test]
+fn test_par_retain() {
+    exercise_par_retain::<ValuePolicy>();
+    exercise_par_retain::<NodePolicy>();
+    exercise_par_retain::<VectorPolicy>();
+    exercise_par_retain::<FastPolicy>();
+}
+
+fn exercise_par_drain<P: StoragePolicy>() {
+    let mut map = filled::<P>(1000);
+    let capacity = map.capacity();
+    let mut drained: Vec<(u64, u64)> = map.par_drain().collect();
+    drained.sort_unstable();
+    assert_eq!(drained, (0..1000).map(|i| (i, i * 10)).collect::<Vec<_>>());
+    assert!(map.is_empty());
+    assert_eq!(map.capacity(), capacity);
+    assert_eq!(map.get(&1), None);
+
+    map.insert(1, 1).unwrap();
+    assert_eq!(map.get(&1), Some(&1));
+
+    // 提前结束或未被驱动时，剩余元素各析构一次
+    let token = Arc::new(());
+    let mut map = Map::<Arc<()>, P>::new().unwrap();
+    for i in 0..1000 {
+        map.insert(i, Arc::clone(&token)).unwrap();
+    }
+    assert!(map.par_drain().find_any(|(key, _)| *key == 500).is_some());
+    assert!(map.is_empty());
+    assert_eq!(Arc::strong_count(&token), 1);
+
+    for i in 0..1000 {
+        map.insert(i, Arc::clone(&token)).unwrap();
+    }
+    drop(map.par_drain());
+    assert!(map.is_empty());
+    assert_eq!(Arc::strong_count(&token), 1);
+}
+
+#[test]
+fn test_par_drain() {
+    exercise_par_drain::<ValuePolicy>();
+    exercise_par_drain::<NodePolicy>();
+    exercise_par_drain::<VectorPolicy>();
+    exercise_par_drain::<FastPolicy>();
+}
@@ -0,0 +1,186 @@
+//! 顺序锁映射压力测试（`cargo test --features concurrent`）
+//!
+//! 读写并发的数据竞争检查：`cargo +nightly miri test --features concurrent --test seqlock`，
+//! Miri 下只运行规模较小的用例。
+
+#![cfg(feature = "concurrent")]
+
+use f14vectormap::{MapError, SeqLockF14Map};
+use std::{
+    collections::hash_map::RandomState,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
+};
+
+/// 四个字都相同的值，读到写了一半的值时可以立即发现
+type Wide = [u64; 4];
+
+fn wide(v: u64) -> Wide {
+    [v; 4]
+}
+
+#[test]
+fn test_seqlock_basic_operations() {
+    let map = SeqLockF14Map::<u64, f32, RandomState>::with_capacity(100).unwrap();
+    assert_eq!(map.capacity(), 100);
+    assert!(map.is_empty());
+
+    for i in 0..100 {
+        assert_eq!(map.insert(i, i as f32), Ok(None));
+    }
+    assert_eq!(map.len(), 100);
+    assert_eq!(map.insert(100, 0.0), Err(MapError::CapacityExceeded));
+    assert_eq!(map.insert(5, 0.5), Ok(Some(5.0)));
+    assert_eq!(map.get(&5), Some(0.5));
+
+    for i in (0..100).step_by(2) {
+        assert!(map.remove(&i).is_some());
+    }
+    assert_eq!(map.len(), 50);
+    for i in 0..100 {
+        assert_eq!(map.contains_key(&i), i % 2 == 1);
+    }
+    // 删除腾出的空间可以再次使用
+    assert_eq!(map.insert(100, 1.0), Ok(None));
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.get(&1), None);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_seqlock_readers_never_see_torn_values() {
+    const KEYS: u64 = 256;
+
+    let map = SeqLockF14Map::<u64, Wide, RandomState>::with_capacity(KEYS as usize).unwrap();
+    for key in 0..KEYS {
+        map.insert(key, wide(key << 32)).unwrap();
+    }
+    let done = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                let mut reads = 0u64;
+                while !done.load(Ordering::Relaxed) {
+                    for key in 0..KEYS {
+                        let value = map.get(&key).expect("key is never removed");
+                        assert!(value.iter().all(|&word| word == value[0]), "torn read {value:?}");
+                        assert_eq!(value[0] >> 32, key);
+                        reads += 1;
+                    }
+                }
+                assert!(reads > 0);
+            });
+        }
+        scope.spawn(|| {
+            for round in 1..=2000u64 {
+                for key in (round % 4..KEYS).step_by(4) {
+                    map.insert(key, wide((key << 32) | round)).unwrap();
+                }
+            }
+            done.store(true, Ordering::Relaxed);
+        });
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_seqlock_concurrent_insert_remove() {
+    const STABLE: u64 = 128;
+    const CHURN: u64 = 512;
+
+    let map = SeqLockF14Map::<u64, u64, RandomState>::with_capacity((STABLE + CHURN) as usize).unwrap();
+    for key in 0..STABLE {
+        map.insert(key, key * 10).unwrap();
+    }
+    let writers_done = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                while writers_done.load(Ordering::Relaxed) < 2 {
+                    // 其他键的增删不能让常驻键暂时消失
+                    for key in 0..STABLE {
+                        assert_eq!(map.get(&key), Some(key * 10));
+                    }
+                    for key in STABLE..STABLE + CHURN {
+                        if let Some(value) = map.get(&key) {
+                            assert_eq!(value, key * 10);
+                        }
+                    }
+                }
+            });
+        }
+        // 两个写者竞争同一把写锁
+        for writer in 0..2u64 {
+            let (map, writers_done) = (&map, &writers_done);
+            scope.spawn(move || {
+                for _ in 0..50 {
+                    for key in (STABLE + writer..STABLE + CHURN).step_by(2) {
+                        map.insert(key, key * 10).unwrap();
+                    }
+                    for key in (STABLE + writer..STABLE + CHURN).step_by(2) {
+                        assert_eq!(map.remove(&key), Some(key * 10));
+                    }
+                }
+                writers_done.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+    });
+    assert_eq!(map.len() as u64, STABLE);
+}
+
+#[test]
+fn test_seqlock_partial_words() {
+    // 键值大小都不是 8 的倍数，槽位末尾的字只用了一部分
+    let map = SeqLockF14Map::<[u8; 3], u16, RandomState>::with_capacity(300).unwrap();
+    for i in 0..300u16 {
+        let key = [i as u8, (i >> 8) as u8, 7];
+        assert_eq!(map.insert(key, i), Ok(None));
+    }
+    for i in 0..300u16 {
+        assert_eq!(map.get(&[i as u8, (i >> 8) as u8, 7]), Some(i));
+    }
+    assert_eq!(map.get(&[0, 0, 0]), None);
+
+    let unit = SeqLockF14Map::<u32, (), RandomState>::with_capacity(4).unwrap();
+    assert_eq!(unit.insert(1, ()), Ok(None));
+    assert_eq!(unit.get(&1), Some(()));
+}
+
+#[test]
+fn test_seqlock_concurrent_read_write_small() {
+    // 规模足够小，可以在 Miri 下检查读者与写者之间没有数据竞争
+    const KEYS: u64 = 8;
+    const ROUNDS: u64 = if cfg!(miri) { 20 } else { 2000 };
+
+    let map = SeqLockF14Map::<u64, Wide, RandomState>::with_capacity(KEYS as usize).unwrap();
+    for key in 0..KEYS {
+        map.insert(key, wide(key << 32)).unwrap();
+    }
+    let done = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    for key in 0..KEYS {
+                        let value = map.get(&key).expect("key is never removed");
+                        assert!(value.iter().all(|&word| word == value[0]), "torn read {value:?}");
+                        assert_eq!(value[0] >> 32, key);
+                    }
+                }
+            });
+        }
+        scope.spawn(|| {
+            for round in 1..=ROUNDS {
+                for key in 0..KEYS {
+                    map.insert(key, wide((key << 32) | round)).unwrap();
+                }
+            }
+            done.store(true, Ordering::Relaxed);
+        });
+    });
+}
@@ -0,0 +1,99 @@
+//! 快照映射测试
+
+use f14vectormap::{F14VectorMap, SnapshotF14Map};
+use std::{collections::hash_map::RandomState, sync::Arc, thread};
+
+#[test]
+fn test_snapshot_publish() {
+    let map = SnapshotF14Map::<u64, String, RandomState>::new().unwrap();
+    assert_eq!(map.version(), 0);
+    assert!(map.load().is_empty());
+
+    let version = map
+        .publish((0..100u64).map(|i| (i, Some(i.to_string()))))
+        .unwrap();
+    assert_eq!(version, 1);
+
+    // 旧快照不受后续发布影响
+    let before = map.load();
+    let version = map
+        .publish((0..50u64).map(|i| (i, if i % 2 == 0 { None } else { Some(format!("v{i}")) })))
+        .unwrap();
+    assert_eq!(version, 2);
+    assert_eq!(before.batch_version(), 1);
+    assert_eq!(before.len(), 100);
+    assert_eq!(before.get(&0).map(String::as_str), Some("0"));
+
+    let after = map.load();
+    assert_eq!(after.batch_version(), 2);
+    assert_eq!(after.len(), 75);
+    assert_eq!(after.get(&0), None);
+    assert_eq!(map.get(&1), Some("v1".to_string()));
+    assert_eq!(map.get(&99), Some("99".to_string()));
+}
+
+#[test]
+fn test_snapshot_from_map() {
+    let mut initial = F14VectorMap::<u64, u64, RandomState>::new().unwrap();
+    initial.insert(1, 10).unwrap();
+    let map = SnapshotF14Map::from_map(initial);
+    assert_eq!(map.get(&1), Some(10));
+    assert_eq!(map.publish(std::iter::empty()).unwrap(), 1);
+    assert_eq!(map.get(&1), Some(10));
+
+    // 版本号沿用映射自身的批量更新版本号
+    let mut initial = F14VectorMap::<u64, u64, RandomState>::new().unwrap();
+    initial.apply_batch([(1, Some(10)), (2, Some(20))]).unwrap();
+    initial.apply_batch([(2, None)]).unwrap();
+    let map = SnapshotF14Map::from_map(initial);
+    assert_eq!(map.version(), 2);
+    assert_eq!(map.publish([(3, Some(30))]).unwrap(), 3);
+    let snapshot = map.load();
+    assert_eq!(snapshot.batch_version(), 3);
+    assert_eq!(map.version(), snapshot.batch_version());
+}
+
+#[test]
+fn test_old_versions_freed() {
+    let value = Arc::new(7u64);
+    let map = SnapshotF14Map::<u64, Arc<u64>, RandomState>::new().unwrap();
+    map.publish([(1, Some(value.clone()))]).unwrap();
+    assert_eq!(Arc::strong_count(&value), 2);
+
+    let reader = map.load();
+    map.publish([(1, None)]).unwrap();
+    // 读者仍持有旧版本
+    assert_eq!(Arc::strong_count(&value), 2);
+    assert_eq!(reader.get(&1).map(|v| **v), Some(7));
+
+    drop(reader);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn test_readers_during_publish() {
+    let map = SnapshotF14Map::<u64, u64, RandomState>::new().unwrap();
+    map.publish((0..1000u64).map(|i| (i, Some(0)))).unwrap();
+
+    // 每批把所有值设为同一个版本号，读者看到的任一版本内部都一致
+    thread::scope(|scope| {
+        let map = &map;
+        for _ in 0..4 {
+            scope.spawn(move || {
+                for _ in 0..500 {
+                    let snapshot = map.load();
+                    let expected = snapshot.batch_version() - 1;
+                    assert_eq!(snapshot.len(), 1000);
+                    assert!(snapshot.iter().all(|(_, &v)| v == expected));
+                }
+            });
+        }
+        scope.spawn(move || {
+            for round in 0..40u64 {
+                let version = map.publish((0..1000u64).map(|i| (i, Some(round + 1)))).unwrap();
+                assert_eq!(version, round + 2);
+            }
+        });
+    });
+    assert_eq!(map.version(), 41);
+}